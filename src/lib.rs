@@ -3,18 +3,22 @@
 //! Simple crate for SPAYD (Short Payment Descriptor) generation
 //! # Example
 //! ```
+//! use spayd_rs::Spayd;
+//!
 //! let spayd = Spayd::builder()
-//!     .account("CZ7907000000001234567890".to_string())
+//!     .account("CZ8207000000001234567890".to_string())
 //!     .amount("239.50".to_string())
+//!     .notify_email("email@example.com")
 //!     .build();
-//! 
+//!
 //! let result = spayd.spayd_string().unwrap();
-//! 
-//! // "SPD*1.0*ACC:CZ7907000000001234567890*AM:239.50"
+//!
+//! // "SPD*1.0*ACC:CZ8207000000001234567890*AM:239.50*NT:E*NTA:email@example.com"
 //! ```
-//! 
+//!
 //! # TODO
 //! - [x] SPAYD string generation
+//! - [x] SPAYD string parsing
 //! - [ ] QR code generation as an optional feature
 
 mod spayd;
@@ -1,9 +1,74 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
 use iso_4217::*;
 use regex::Regex;
 use typed_builder::TypedBuilder;
 
-#[cfg(feature = "qrcode")]
-use qrcode::QrResult;
+mod access;
+mod account;
+mod amount;
+mod borrowed;
+mod crc;
+mod cross;
+mod currency;
+mod czech;
+mod date;
+mod encoding;
+mod eq;
+mod iban;
+mod idn;
+mod iter;
+mod key;
+pub mod limits;
+mod map;
+mod notify;
+mod parse;
+mod payment_type;
+mod profile;
+#[cfg(feature = "qr-decode")]
+mod qr;
+mod query;
+mod reference;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod text;
+mod uri;
+mod validated;
+pub mod validators;
+mod version;
+
+pub use account::Account;
+pub use amount::{normalize_amount, Amount, AmountFormat};
+pub use borrowed::SpaydRef;
+pub use cross::{CrossFieldRules, Inconsistency};
+pub use currency::IntoCurrency;
+pub use czech::CzechAccount;
+pub use date::SpaydDate;
+pub use encoding::EscapePolicy;
+use encoding::{encode, encode_canonical};
+pub use iban::Iban;
+pub use iso_4217::CurrencyCode;
+pub use iter::Iter;
+pub use key::{FieldOrder, ParseKeyError, SpaydKey};
+pub use map::SpaydMapError;
+pub use notify::Notification;
+pub use parse::{
+    CrcMismatchPolicy, DuplicateKeyPolicy, ParseOptions, SpaydParseError, SpaydWarning,
+};
+pub use profile::{ProfileWarning, ValidationProfile};
+#[cfg(feature = "qr-decode")]
+pub use qr::SpaydDecodeError;
+pub use query::QueryOptions;
+pub use reference::Reference;
+pub use text::{
+    message_lossy, message_lossy_with, recipient_translit, recipient_translit_with, transliterate,
+    Adjustment, LossyText, SanitizeReport, TransliterationTable,
+};
+pub use validated::ValidatedSpayd;
+pub use version::SpaydVersion;
 
 /// Error enum
 #[derive(Debug, PartialEq)]
@@ -11,6 +76,17 @@ pub enum SpaydError {
     /// Invalid account
     InvalidAccountNumber(&'static str),
 
+    /// Invalid list of alternative accounts
+    InvalidAltAccount(&'static str),
+
+    /// Alternative account failing the checks of `ACC`
+    InvalidAltAccountEntry {
+        /// Position of the account in the list, from 0
+        index: usize,
+        /// Error of the account
+        source: Box<SpaydError>,
+    },
+
     /// Invalid amount
     InvalidAmount(&'static str),
 
@@ -32,22 +108,387 @@ pub enum SpaydError {
     /// Invalid message
     InvalidMessage(&'static str),
 
+    /// Invalid notify type
+    InvalidNotifyType(&'static str),
+
     /// Invalid notify address
     InvalidNotifyAddress(&'static str),
+
+    /// IBAN length does not match its country
+    InvalidIbanLength {
+        /// Country code
+        country: &'static str,
+        /// Expected IBAN length for the country
+        expected: usize,
+        /// Actual IBAN length
+        actual: usize,
+    },
+
+    /// Amount is longer than the specification allows
+    AmountTooLong {
+        /// Length of the amount
+        len: usize,
+        /// Maximum length
+        max: usize,
+    },
+
+    /// Amount is not a decimal number
+    AmountNotDecimal,
+
+    /// Amount has more than two decimal places
+    AmountTooManyDecimals {
+        /// Number of decimal places of the amount
+        found: usize,
+    },
+
+    /// Amount exceeds [`ValidationOptions::max_amount`]
+    AmountOutOfRange,
+
+    /// Amount has more decimal places than its currency allows
+    TooManyDecimalPlaces {
+        /// Currency code
+        currency: &'static str,
+        /// Number of decimal places the currency allows
+        allowed: u32,
+    },
+
+    /// IBAN country is not in [`ValidationOptions::allowed_countries`]
+    CountryNotAllowed {
+        /// Country code
+        country: [u8; 2],
+    },
+
+    /// Currency is not in [`ValidationOptions::allowed_currencies`]
+    CurrencyNotAllowed {
+        /// Currency code
+        currency: CurrencyCode,
+        /// Allowed currencies
+        allowed: Vec<CurrencyCode>,
+    },
+
+    /// Currency is a valid code, but no active national currency (e.g. `XAU`, gold), see
+    /// [`ValidationOptions::reject_unusual_currencies`]
+    CurrencyNotPermitted {
+        /// Currency code
+        currency: CurrencyCode,
+    },
+
+    /// Value is longer than the specification allows once percent-encoded
+    EncodedTooLong {
+        /// Key of the field
+        key: SpaydKey,
+        /// Length of the value in characters
+        len: usize,
+        /// Length of the percent-encoded value in characters
+        encoded_len: usize,
+        /// Maximum length in characters
+        max: usize,
+    },
+
+    /// Due date is before today, see [`ValidationOptions::reject_past_dates`]
+    DateInPast {
+        /// Due date
+        date: SpaydDate,
+        /// Date used as today
+        today: SpaydDate,
+    },
+
+    /// Value exceeds a limit of [`ValidationOptions::profile`]
+    RejectedByProfile {
+        /// Key of the field
+        key: SpaydKey,
+        /// Length of the value in characters
+        len: usize,
+        /// Maximum length the bank accepts
+        max: usize,
+    },
+
+    /// Fields are inconsistent with each other, see [`CrossFieldRules`]
+    Inconsistent(Inconsistency),
+
+    /// Value is longer than the specification allows, see [`limits`]
+    TooLong {
+        /// Key of the field
+        key: SpaydKey,
+        /// Length of the value in characters
+        len: usize,
+        /// Maximum length in characters
+        max: usize,
+    },
+
+    /// Generated string contains a non-ASCII character, see
+    /// [`ValidationOptions::require_ascii_payload`]
+    NonAsciiPayload {
+        /// Key of the field containing the character
+        key: SpaydKey,
+        /// First non-ASCII character of the field
+        character: char,
+    },
+
+    /// Name of a field not modelled by this crate can't be written
+    InvalidCustomKey {
+        /// Key of the field
+        key: SpaydKey,
+        /// Why the name is rejected
+        reason: &'static str,
+    },
+
+    /// `CRC32` field doesn't match the other fields, see [`CrcMismatchPolicy`]
+    CrcMismatch {
+        /// Checksum as written in the payload
+        expected: String,
+        /// Checksum of the other fields
+        computed: String,
+    },
+
+    /// Generated string exceeds [`ValidationOptions::max_payload_len`]
+    PayloadTooLong {
+        /// Length of the string in bytes
+        len: usize,
+        /// Maximum length in bytes
+        max: usize,
+    },
+}
+
+impl SpaydError {
+    /// Key of the field the error relates to
+    ///
+    /// [`SpaydError::PayloadTooLong`] relates to no single field; its key is
+    /// [`SpaydKey::Custom`] with an empty name.
+    pub fn key(&self) -> SpaydKey {
+        match self {
+            SpaydError::InvalidAccountNumber(_)
+            | SpaydError::InvalidIbanLength { .. }
+            | SpaydError::CountryNotAllowed { .. } => SpaydKey::Acc,
+            SpaydError::InvalidAltAccount(_) | SpaydError::InvalidAltAccountEntry { .. } => {
+                SpaydKey::AltAcc
+            }
+            SpaydError::InvalidAmount(_)
+            | SpaydError::AmountTooLong { .. }
+            | SpaydError::AmountNotDecimal
+            | SpaydError::AmountTooManyDecimals { .. }
+            | SpaydError::AmountOutOfRange
+            | SpaydError::TooManyDecimalPlaces { .. } => SpaydKey::Am,
+            SpaydError::InvalidCurrency(_)
+            | SpaydError::CurrencyNotAllowed { .. }
+            | SpaydError::CurrencyNotPermitted { .. } => SpaydKey::Cc,
+            SpaydError::InvalidReference(_) => SpaydKey::Rf,
+            SpaydError::InvalidRecipient(_) => SpaydKey::Rn,
+            SpaydError::InvalidDate(_) | SpaydError::DateInPast { .. } => SpaydKey::Dt,
+            SpaydError::InvalidPaymentType(_) => SpaydKey::Pt,
+            SpaydError::InvalidMessage(_) => SpaydKey::Msg,
+            SpaydError::InvalidNotifyType(_) => SpaydKey::Nt,
+            SpaydError::InvalidNotifyAddress(_) => SpaydKey::Nta,
+            SpaydError::EncodedTooLong { key, .. }
+            | SpaydError::RejectedByProfile { key, .. }
+            | SpaydError::TooLong { key, .. }
+            | SpaydError::NonAsciiPayload { key, .. }
+            | SpaydError::InvalidCustomKey { key, .. } => key.clone(),
+            SpaydError::Inconsistent(inconsistency) => inconsistency.key(),
+            SpaydError::CrcMismatch { .. } => SpaydKey::Custom("CRC32".to_string()),
+            SpaydError::PayloadTooLong { .. } => SpaydKey::Custom(String::new()),
+        }
+    }
+
+    /// Human readable description of the error
+    pub fn message(&self) -> &'static str {
+        match self {
+            SpaydError::InvalidAccountNumber(msg)
+            | SpaydError::InvalidAltAccount(msg)
+            | SpaydError::InvalidAmount(msg)
+            | SpaydError::InvalidCurrency(msg)
+            | SpaydError::InvalidReference(msg)
+            | SpaydError::InvalidRecipient(msg)
+            | SpaydError::InvalidDate(msg)
+            | SpaydError::InvalidPaymentType(msg)
+            | SpaydError::InvalidMessage(msg)
+            | SpaydError::InvalidNotifyType(msg)
+            | SpaydError::InvalidNotifyAddress(msg) => msg,
+            SpaydError::InvalidAltAccountEntry { source, .. } => source.message(),
+            SpaydError::InvalidIbanLength { .. } => "IBAN length does not match its country",
+            SpaydError::CountryNotAllowed { .. } => "Country not allowed",
+            SpaydError::CurrencyNotAllowed { .. } => "Currency not allowed",
+            SpaydError::CurrencyNotPermitted { .. } => {
+                "Currency code is recognized, but not permitted on a payment"
+            }
+            SpaydError::AmountTooLong { .. } | SpaydError::TooLong { .. } => {
+                "Exceeded maximum length"
+            }
+            SpaydError::AmountNotDecimal => {
+                "Value is not in a decimal format. Maximum number of decimal places is 2."
+            }
+            SpaydError::AmountTooManyDecimals { .. } => "Maximum number of decimal places is 2",
+            SpaydError::AmountOutOfRange => "Amount exceeds the maximum allowed value",
+            SpaydError::TooManyDecimalPlaces { .. } => "Too many decimal places for the currency",
+            SpaydError::EncodedTooLong { .. } => "Exceeded maximum length once percent-encoded",
+            SpaydError::DateInPast { .. } => "Date is in the past",
+            SpaydError::Inconsistent(inconsistency) => inconsistency.message(),
+            SpaydError::RejectedByProfile { .. } => "Exceeded the bank's maximum length",
+            SpaydError::NonAsciiPayload { .. } => "Value contains a non-ASCII character",
+            SpaydError::InvalidCustomKey { reason, .. } => reason,
+            SpaydError::CrcMismatch { .. } => "Checksum does not match the payload",
+            SpaydError::PayloadTooLong { .. } => "Payload exceeds the maximum length",
+        }
+    }
+
+    /// Write the description of the error, with details where available
+    pub(crate) fn fmt_message(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydError::InvalidAltAccountEntry { index, source } => {
+                write!(f, "Entry {}: ", index + 1)?;
+                source.fmt_message(f)
+            }
+            SpaydError::InvalidIbanLength {
+                country,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "IBAN for country {} must have {} characters, got {}",
+                country, expected, actual
+            ),
+            SpaydError::AmountTooLong { len, max } | SpaydError::TooLong { len, max, .. } => {
+                write!(
+                    f,
+                    "Exceeded maximum length of {} characters, got {}",
+                    max, len
+                )
+            }
+            SpaydError::AmountTooManyDecimals { found } => {
+                write!(f, "Maximum number of decimal places is 2, got {}", found)
+            }
+            SpaydError::TooManyDecimalPlaces {
+                currency,
+                allowed: 0,
+            } => write!(f, "{} does not allow decimal places", currency),
+            SpaydError::TooManyDecimalPlaces { currency, allowed } => {
+                write!(f, "{} allows at most {} decimal places", currency, allowed)
+            }
+            SpaydError::CountryNotAllowed { country } => write!(
+                f,
+                "Country {} not allowed",
+                String::from_utf8_lossy(country)
+            ),
+            SpaydError::CurrencyNotAllowed { currency, allowed } => {
+                write!(
+                    f,
+                    "Currency {} not allowed, expected one of ",
+                    currency.alpha()
+                )?;
+
+                for (i, code) in allowed.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    f.write_str(code.alpha())?;
+                }
+
+                Ok(())
+            }
+            SpaydError::CurrencyNotPermitted { currency } => write!(
+                f,
+                "Currency code {} ({}) is recognized, but not permitted on a payment",
+                currency.alpha(),
+                currency.name()
+            ),
+            SpaydError::EncodedTooLong {
+                len,
+                encoded_len,
+                max,
+                ..
+            } => write!(
+                f,
+                "Exceeded maximum length of {} characters, got {} characters ({} percent-encoded)",
+                max, len, encoded_len
+            ),
+            SpaydError::DateInPast { date, today } => {
+                write!(f, "Date {} is before today ({})", date, today)
+            }
+            SpaydError::RejectedByProfile { len, max, .. } => write!(
+                f,
+                "Exceeded the bank's maximum length of {} characters, got {}",
+                max, len
+            ),
+            SpaydError::NonAsciiPayload { character, .. } => write!(
+                f,
+                "Value contains non-ASCII character '{}' (U+{:04X})",
+                character, *character as u32
+            ),
+            SpaydError::CrcMismatch { expected, computed } => write!(
+                f,
+                "Checksum {} does not match the payload, computed {}",
+                expected, computed
+            ),
+            SpaydError::PayloadTooLong { len, max } => write!(
+                f,
+                "Payload has {} bytes, exceeding the maximum of {} bytes",
+                len, max
+            ),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl fmt::Display for SpaydError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydError::PayloadTooLong { .. } => f.write_str("invalid payload: ")?,
+            SpaydError::InvalidCustomKey { key, .. } => {
+                write!(f, "invalid key {:?}: ", key.as_str())?
+            }
+            _ => write!(f, "invalid {}: ", self.key())?,
+        }
+
+        self.fmt_message(f)
+    }
+}
+
+impl std::error::Error for SpaydError {}
+
+/// Error returned when a payment QR code cannot be generated
+#[cfg(feature = "qrcode")]
+#[derive(Debug, PartialEq)]
+pub enum SpaydQrError {
+    /// Payment doesn't pass validation
+    InvalidPayment(SpaydError),
+
+    /// SPAYD string doesn't fit into a QR code
+    QrCode(qrcode::types::QrError),
+}
+
+#[cfg(feature = "qrcode")]
+impl fmt::Display for SpaydQrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydQrError::InvalidPayment(e) => e.fmt(f),
+            SpaydQrError::QrCode(e) => write!(f, "QR code cannot be generated: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "qrcode")]
+impl std::error::Error for SpaydQrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpaydQrError::InvalidPayment(e) => Some(e),
+            SpaydQrError::QrCode(e) => Some(e),
+        }
+    }
 }
 
 /// Payment type
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PaymentType {
     /// Instant payment (if the bank supports it)
     Instant,
 
-    /// Other payment type (max. 3 characters)
+    /// Other payment type (max. 3 uppercase letters and digits)
     Other(String),
 }
 
 /// Notify type
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NotifyType {
     /// Phone notification
     Phone,
@@ -56,240 +497,1073 @@ pub enum NotifyType {
     Email,
 }
 
+/// Validation options
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// How strictly the payment is validated, [`ValidationLevel::Standard`] by default
+    pub level: ValidationLevel,
+
+    /// Reject IBANs with a country code missing from the IBAN registry
+    ///
+    /// By default such IBANs are only checked for the generic format and check digits.
+    pub reject_unknown_iban_countries: bool,
+
+    /// Accept only IBANs from these countries (e.g. `*b"CZ"`); all countries are accepted if `None`
+    pub allowed_countries: Option<HashSet<[u8; 2]>>,
+
+    /// Check the account prefix and number embedded in `CZ` IBANs with the Czech modulo-11 rule
+    pub check_czech_account: bool,
+
+    /// Maximum amount in hundredths (e.g. cents), `999_999_999` (9 999 999.99) by default
+    pub max_amount: u64,
+
+    /// Minimum number of digits of a phone number in `NTA`, `7` by default
+    pub min_phone_digits: usize,
+
+    /// Maximum number of digits of a phone number in `NTA`, `15` by default (E.164)
+    pub max_phone_digits: usize,
+
+    /// Accept a zero amount (`AM:0`), `true` by default
+    pub allow_zero_amount: bool,
+
+    /// Check decimal places of the amount against the currency (none for `JPY`, three for
+    /// `BHD`); without a currency, at most two decimal places are allowed
+    pub currency_decimals: bool,
+
+    /// Accept only these currencies; all currencies are accepted if `None`
+    pub allowed_currencies: Option<Vec<CurrencyCode>>,
+
+    /// Reject currencies that are no active national currency: funds codes, precious metals
+    /// (`XAU`), accounting units (`XDR`) and retired currencies (`HRK`)
+    pub reject_unusual_currencies: bool,
+
+    /// Currencies accepted despite [`ValidationOptions::reject_unusual_currencies`]
+    pub accepted_unusual_currencies: Vec<CurrencyCode>,
+
+    /// Currencies rejected by [`ValidationOptions::reject_unusual_currencies`], in addition
+    /// to the built-in list
+    pub rejected_currencies: Vec<CurrencyCode>,
+
+    /// Currency checked against [`ValidationOptions::allowed_currencies`] when `CC` is unset,
+    /// `CZK` by default; with `None`, a payment without a currency is always accepted
+    pub default_currency: Option<CurrencyCode>,
+
+    /// Maximum length of the generated string in bytes, `600` by default (the alphanumeric
+    /// capacity of a version 15 QR code with error correction level M); unlimited if `None`
+    pub max_payload_len: Option<usize>,
+
+    /// Reject a generated string containing non-ASCII characters after encoding (e.g. in
+    /// extras, which are written as given)
+    pub require_ascii_payload: bool,
+
+    /// How characters outside the allowed character set are handled in `RN`, `MSG` and `PT`;
+    /// they fail validation by default
+    pub escape: EscapePolicy,
+
+    /// Reject payment types missing from [`PaymentType::KNOWN_CODES`]
+    pub reject_unknown_payment_types: bool,
+
+    /// Reject due dates before today
+    pub reject_past_dates: bool,
+
+    /// Date used as today by [`ValidationOptions::reject_past_dates`]; the system date (in
+    /// UTC) if `None`
+    pub today: Option<SpaydDate>,
+
+    /// Limits of the recipient's bank, checked in addition to the specification
+    pub profile: Option<ValidationProfile>,
+
+    /// Checks of field combinations done with [`ValidationLevel::Strict`]
+    pub cross_field_rules: CrossFieldRules,
+}
+
+/// How strictly a payment is validated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationLevel {
+    /// Also reject unknown IBAN countries, invalid Czech account numbers, amounts with too
+    /// many decimal places for the currency, unusual currencies, unknown payment types and
+    /// inconsistent field combinations (see [`CrossFieldRules`])
+    Strict,
+
+    /// Apply the checks enabled in [`ValidationOptions`]
+    #[default]
+    Standard,
+
+    /// Repair what can be repaired (see [`Spayd::sanitize`]) before the checks, on a copy
+    Lenient,
+}
+
+impl ValidationOptions {
+    fn strict(&self) -> bool {
+        self.level == ValidationLevel::Strict
+    }
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            level: ValidationLevel::Standard,
+            reject_unknown_iban_countries: false,
+            allowed_countries: None,
+            check_czech_account: false,
+            max_amount: 999_999_999,
+            min_phone_digits: 7,
+            max_phone_digits: 15,
+            allow_zero_amount: true,
+            currency_decimals: false,
+            allowed_currencies: None,
+            reject_unusual_currencies: false,
+            accepted_unusual_currencies: Vec::new(),
+            rejected_currencies: Vec::new(),
+            default_currency: Some(CurrencyCode::CZK),
+            max_payload_len: Some(600),
+            require_ascii_payload: false,
+            escape: EscapePolicy::Error,
+            reject_unknown_payment_types: false,
+            reject_past_dates: false,
+            today: None,
+            profile: None,
+            cross_field_rules: CrossFieldRules::default(),
+        }
+    }
+}
+
 /// SPAYD data structure
-#[derive(Debug, TypedBuilder)]
+///
+/// Text given to the builder has surrounding whitespace (including non-breaking spaces)
+/// trimmed; spaces inside the recipient or message are kept.
+#[derive(Debug, Clone, TypedBuilder)]
 pub struct Spayd {
+    /// IBAN, normalized to the electronic format (`CZ55 0800 ...` becomes `CZ550800...`)
+    ///
+    /// Accepts a [`String`] as well as an already validated [`Iban`].
+    #[builder(setter(
+        transform = |account: impl Into<String>| iban::normalize(account.into().into()).into_owned()
+    ))]
     account: String,
-    amount: String,
 
-    #[builder(default, setter(strip_option))]
+    /// BIC of the recipient's bank, written after the IBAN as `ACC:IBAN+BIC`
+    #[builder(default, setter(
+        transform = |bic: impl Into<String>| Some(text::trim(&bic.into()).to_string())
+    ))]
+    bic: Option<String>,
+
+    /// Alternative accounts (`ALT-ACC`), at most two, e.g. at other banks so the payer can
+    /// avoid cross-bank fees
+    #[builder(default)]
+    alt_accounts: Vec<Account>,
+
+    /// Amount; accepts a [`String`] as well as an [`Amount`]
+    #[builder(default, setter(
+        transform = |amount: impl Into<String>| Some(text::trim(&amount.into()).to_string())
+    ))]
+    amount: Option<String>,
+
+    /// Currency; accepts a [`String`] as well as a [`CurrencyCode`]
+    #[builder(default, setter(
+        transform = |currency: impl IntoCurrency| Some(text::trim(&currency.into_currency()).to_ascii_uppercase())
+    ))]
     currency: Option<String>,
 
-    #[builder(default, setter(strip_option))]
+    /// Reference; accepts a [`String`] as well as a [`Reference`]
+    #[builder(default, setter(
+        transform = |reference: impl Into<String>| Some(text::trim(&reference.into()).to_string())
+    ))]
     reference: Option<String>,
 
-    #[builder(default, setter(strip_option))]
+    #[builder(default, setter(
+        transform = |recipient: impl Into<String>| Some(text::compose(text::trim(&recipient.into()).into()).into_owned())
+    ))]
     recipient: Option<String>,
 
-    #[builder(default, setter(strip_option))]
+    /// Due date; accepts a [`String`] (`YYYYMMDD` or `YYYY-MM-DD`) as well as a [`SpaydDate`]
+    ///
+    /// `due_in_days` sets it relative to today instead.
+    #[builder(default, setter(
+        transform = |date: impl Into<String>| Some(date::normalize(text::trim(&date.into()).into()).into_owned())
+    ))]
     date: Option<String>,
 
     #[builder(default, setter(strip_option))]
     payment_type: Option<PaymentType>,
 
-    #[builder(default, setter(strip_option))]
+    /// Message; accepts a [`String`] as well as a [`LossyText`]
+    #[builder(default, setter(
+        transform = |message: impl Into<String>| Some(text::compose(text::trim(&message.into()).into()).into_owned())
+    ))]
     message: Option<String>,
 
-    #[builder(default, setter(strip_option))]
-    notify: Option<NotifyType>,
+    /// Notification of the recipient, written as the type (`NT`) with the address (`NTA`)
+    #[builder(default, setter(
+        transform = |notification: Notification| Some(notification.trimmed())
+    ))]
+    notification: Option<Notification>,
+
+    /// Notification type set with [`Spayd::set`], waiting for the address
+    #[builder(default, setter(skip))]
+    pending_notify: Option<NotifyType>,
+
+    /// Fields not modelled by this crate (e.g. `X-VS`), in their original order
+    ///
+    /// Names must not be empty, contain `*` or `:`, or be the name of a modelled field.
+    #[builder(default)]
+    extras: Vec<(String, String)>,
+
+    #[builder(default)]
+    version: SpaydVersion,
+
+    /// Options applied when validating the payment
+    #[builder(default)]
+    validation: ValidationOptions,
+
+    /// How the amount is written in [`Spayd::spayd_string`]
+    #[builder(default)]
+    amount_format: AmountFormat,
+
+    /// Order of the fields in [`Spayd::spayd_string`]
+    #[builder(default)]
+    field_order: FieldOrder,
+
+    /// Uppercase `RN`, `MSG` and `PT` before validation and output (`Novák s.r.o.` becomes
+    /// `NOVÁK S.R.O.`)
+    ///
+    /// Letters with diacritics stay and still fail validation; see [`transliterate`].
+    #[builder(default)]
+    auto_uppercase: bool,
+
+    /// Repair the fields before the checks, on a copy, as with [`ValidationLevel::Lenient`]
+    /// but keeping the level's checks; see [`Spayd::sanitized`] for the changes made
+    #[builder(default)]
+    lossy: bool,
+}
 
-    #[builder(default, setter(strip_option))]
-    notify_address: Option<String>,
+/// Value of an amount in hundredths (e.g. cents), if it's in the decimal format
+fn amount_minor(amount: &str) -> Option<u64> {
+    amount_scaled(amount, 2)
+}
+
+/// Value of an amount in units of `10^-decimals`, if it has at most `decimals` decimal places
+fn amount_scaled(amount: &str, decimals: u32) -> Option<u64> {
+    let (int, frac) = match amount.split_once('.') {
+        Some((_, "")) => return None,
+        Some(parts) => parts,
+        None => (amount, ""),
+    };
+
+    if frac.len() > decimals as usize
+        || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let int: u64 = int.parse().ok()?;
+    let frac: u64 = match decimals {
+        0 => 0,
+        _ => format!("{:0<1$}", frac, decimals as usize).parse().ok()?,
+    };
+
+    int.checked_mul(10u64.pow(decimals))?.checked_add(frac)
+}
+
+/// Write an amount with at least two decimal places
+fn pad_amount(amount: &str) -> String {
+    let (int, frac) = amount.split_once('.').unwrap_or((amount, ""));
+
+    format!("{}.{:0<2}", int, frac)
+}
+
+/// Write a validated amount with exactly two decimal places and no leading zeros
+fn canonical_amount(amount: &str) -> String {
+    let (int, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    let int = int.trim_start_matches('0');
+
+    format!("{}.{:0<2}", if int.is_empty() { "0" } else { int }, frac)
 }
 
 impl Spayd {
     /// Generate SPAYD string
     pub fn spayd_string(&self) -> Result<String, SpaydError> {
+        if self.repairs(&self.validation) {
+            return self.spayd_string_with(&self.validation);
+        }
+
         self.validate()?;
 
         Ok(self.build_string())
     }
 
+    /// Generate SPAYD string, or all validation errors if it doesn't pass validation
+    pub fn spayd_string_report(&self) -> Result<String, Vec<SpaydError>> {
+        if self.repairs(&self.validation) {
+            let (spayd, report) = self.sanitized();
+
+            if !report.errors.is_empty() {
+                return Err(report.errors);
+            }
+
+            return Ok(spayd.build_string());
+        }
+
+        self.validate_all()?;
+
+        Ok(self.build_string())
+    }
+
+    /// Generate SPAYD string, validated with the given options instead of the payment's own
+    pub fn spayd_string_with(&self, options: &ValidationOptions) -> Result<String, SpaydError> {
+        let mut spayd = self.clone();
+        spayd.validation = options.clone();
+
+        if self.repairs(options) {
+            spayd.sanitize()?;
+        } else {
+            spayd.validate()?;
+        }
+
+        Ok(spayd.build_string())
+    }
+
     /// Generate SPAYD string without input data validation
     pub fn spayd_string_unchecked(&self) -> String {
         self.build_string()
     }
 
+    /// Whether the fields are repaired before validating with `options`
+    fn repairs(&self, options: &ValidationOptions) -> bool {
+        self.lossy || options.level == ValidationLevel::Lenient
+    }
+
+    /// Generate normalized SPAYD string
+    ///
+    /// Two semantically equal payments always produce byte-identical output:
+    /// - fields are in [`FieldOrder::SpecCanonical`] order (alphabetically by key, unknown keys
+    ///   included), as the specification defines for CRC computation
+    /// - the amount is written with exactly two decimal places and no leading zeros
+    /// - the account and currency are uppercase
+    /// - `%`, `*` and control characters in values are always percent-encoded
+    pub fn canonical_string(&self) -> Result<String, SpaydError> {
+        self.validate()?;
+
+        let mut pairs = Vec::new();
+
+        for (key, value) in self.output_fields() {
+            let value = match key {
+                SpaydKey::Acc | SpaydKey::AltAcc | SpaydKey::Cc => value.to_ascii_uppercase(),
+                SpaydKey::Am => canonical_amount(&value),
+                SpaydKey::Nta => {
+                    encode_canonical(&notify::written_address(self.notify_type().as_ref(), value))
+                        .into_owned()
+                }
+                SpaydKey::Rn | SpaydKey::Msg | SpaydKey::Pt => match self.validation.escape {
+                    EscapePolicy::Strip => {
+                        encode_canonical(&EscapePolicy::Strip.apply(&value)).into_owned()
+                    }
+                    EscapePolicy::PercentEncode => {
+                        EscapePolicy::PercentEncode.apply(&value).into_owned()
+                    }
+                    EscapePolicy::Error => encode_canonical(&value).into_owned(),
+                },
+                _ => encode_canonical(&value).into_owned(),
+            };
+
+            pairs.push((key, Cow::Owned(value)));
+        }
+
+        Ok(crc::canonical_payload(self.version, pairs))
+    }
+
+    /// Format version, `1.0` unless parsed from a string with a different version
+    pub fn version(&self) -> SpaydVersion {
+        self.version
+    }
+
+    /// Account as a typed [`Iban`]
+    pub fn iban(&self) -> Result<Iban, SpaydError> {
+        Iban::parse(&self.account)
+    }
+
+    /// Account in the Czech domestic format (e.g. `19-2000145399/0800`), if it's a `CZ` IBAN
+    pub fn account_czech_format(&self) -> Option<String> {
+        CzechAccount::from_iban(&self.account).map(|account| account.to_string())
+    }
+
+    /// Value of a field not modelled by this crate (e.g. `X-VS`)
+    pub fn extra(&self, key: &str) -> Option<&str> {
+        self.extras
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Fields not modelled by this crate, in their original order
+    pub fn extras(&self) -> &[(String, String)] {
+        &self.extras
+    }
+
     /// Generate payment QR code
+    ///
+    /// The payment is checked with [`Spayd::validate`] first, as in [`Spayd::spayd_string`].
     #[cfg(feature = "qrcode")]
-    pub fn qrcode(&self) -> QrResult<qrcode::QrCode> {
-        qrcode::QrCode::new(self.spayd_string().unwrap())
+    pub fn qrcode(&self) -> Result<qrcode::QrCode, SpaydQrError> {
+        let content = self.spayd_string().map_err(SpaydQrError::InvalidPayment)?;
+
+        qrcode::QrCode::new(content).map_err(SpaydQrError::QrCode)
+    }
+
+    /// Set fields in the chosen [`FieldOrder`], with the currency of
+    /// [`ValidationOptions::profile`] if `CC` is unset
+    fn output_fields(&self) -> Vec<(SpaydKey, Cow<'_, str>)> {
+        let mut fields: Vec<_> = self.iter().collect();
+        let default_currency = self
+            .validation
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.default_currency);
+
+        if let (None, Some(currency)) = (&self.currency, default_currency) {
+            let at = fields
+                .iter()
+                .take_while(|(key, _)| {
+                    matches!(key, SpaydKey::Acc | SpaydKey::AltAcc | SpaydKey::Am)
+                })
+                .count();
+
+            fields.insert(at, (SpaydKey::Cc, Cow::Borrowed(currency.alpha())));
+        }
+
+        if self.auto_uppercase {
+            for (key, value) in &mut fields {
+                match key {
+                    SpaydKey::Rn | SpaydKey::Msg | SpaydKey::Pt => {
+                        *value = Cow::Owned(value.to_uppercase())
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if self.field_order == FieldOrder::SpecCanonical {
+            fields.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+
+        fields
     }
 
     fn build_string(&self) -> String {
-        let mut v: Vec<String> = Vec::with_capacity(11);
+        let mut v: Vec<String> = Vec::with_capacity(13);
 
         v.push("SPD".to_string()); // header
-        v.push("1.0".to_string()); // version
-        v.push(format!("ACC:{}", self.account));
-        v.push(format!("AM:{}", self.amount));
+        v.push(self.version.to_string()); // version
 
-        if let Some(ref currency) = self.currency {
-            v.push(format!("CC:{}", currency));
+        for (key, value) in self.written_fields() {
+            v.push(format!("{}:{}", key, value));
         }
 
-        if let Some(ref reference) = self.reference {
-            v.push(format!("RF:{}", reference));
-        }
+        v.join("*")
+    }
+
+    /// Fields with their values as written by [`Spayd::spayd_string`]
+    fn written_fields(&self) -> Vec<(SpaydKey, String)> {
+        let mut fields = Vec::with_capacity(11);
+
+        for (key, value) in self.output_fields() {
+            let value = match key {
+                SpaydKey::Am if self.amount_format == AmountFormat::TwoDecimals => {
+                    pad_amount(&value).into()
+                }
+                SpaydKey::Nta => notify::written_address(self.notify_type().as_ref(), value),
+                _ => value,
+            };
+            let value = match key {
+                SpaydKey::Rn | SpaydKey::Msg | SpaydKey::Pt => self.validation.escape.apply(&value),
+                _ if self.validation.escape == EscapePolicy::PercentEncode => {
+                    encode_canonical(&value)
+                }
+                _ => encode(&value),
+            };
 
-        if let Some(ref recipient) = self.recipient {
-            v.push(format!("RN:{}", recipient));
+            fields.push((key, value.into_owned()));
         }
 
-        if let Some(ref date) = self.date {
-            v.push(format!("DT:{}", date));
+        fields
+    }
+
+    /// Check the generated string for non-ASCII characters, if
+    /// [`ValidationOptions::require_ascii_payload`] is set
+    fn check_ascii(&self) -> Result<(), SpaydError> {
+        if !self.validation.require_ascii_payload {
+            return Ok(());
         }
 
-        if let Some(ref payment_type) = self.payment_type {
-            let pt = match payment_type {
-                PaymentType::Instant => "IP",
-                PaymentType::Other(s) => s,
-            };
+        for (key, value) in self.written_fields() {
+            let name = key.to_string();
 
-            v.push(format!("PT:{}", pt));
+            if let Some(character) = name.chars().chain(value.chars()).find(|c| !c.is_ascii()) {
+                return Err(SpaydError::NonAsciiPayload { key, character });
+            }
         }
 
-        if let Some(ref message) = self.message {
-            v.push(format!("MSG:{}", message));
+        Ok(())
+    }
+
+    /// Length of the generated string in bytes
+    pub fn payload_len(&self) -> usize {
+        self.build_string().len()
+    }
+
+    /// Check the payment against its validation options, stopping at the first error
+    ///
+    /// Doesn't generate the string. Fields are checked as set, even with
+    /// [`ValidationLevel::Lenient`].
+    pub fn validate(&self) -> Result<(), SpaydError> {
+        if let Some(spayd) = self.uppercased() {
+            return spayd.validate();
         }
 
-        if let Some(ref notify) = self.notify {
-            let val = match notify {
-                NotifyType::Phone => "P",
-                NotifyType::Email => "E",
-            };
-            v.push(format!("NT:{}", val));
+        self.fields().validate()?;
+
+        if let Some(error) = self.extras_errors().next() {
+            return Err(error);
         }
 
-        if let Some(ref notify_address) = self.notify_address {
-            v.push(format!("NTA:{}", notify_address));
+        match self.payload_errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
+    }
 
-        v.join("*")
+    /// Errors of the names of the fields not modelled by this crate
+    fn extras_errors(&self) -> impl Iterator<Item = SpaydError> + '_ {
+        self.extras
+            .iter()
+            .filter_map(|(key, _)| SpaydKey::validate_custom(key).err())
     }
 
-    fn validate(&self) -> Result<(), SpaydError> {
-        let re_iban = Regex::new(r"^[A-Z]{2}\d{2}[0-9A-Z]{1,30}$").expect("IBAN regex is valid");
-        let re_amount = Regex::new(r"^\d+(\.\d{1,2})?$").expect("Amount regex is valid");
-        let re_digits = Regex::new(r"^[0-9]+$").expect("Digits-only regex is valid");
-        let re_all_allowed =
-            Regex::new(r"^[0-9A-Z $%+\-./:]+$").expect("Allowed characters regex is valid");
-        let re_date = Regex::new(r"^([12]\d{3}(0[1-9]|1[0-2])(0[1-9]|[12]\d|3[01]))$")
-            .expect("Date regex is valid");
-        let re_phone = Regex::new(r"^\+?\d+$").expect("Phone regex is valid");
-        let re_email = Regex::new(
-            r"^([a-z0-9_+]([a-z0-9_+.]*[a-z0-9_+])?)@([a-z0-9]+([\-\.]{1}[a-z0-9]+)*\.[a-z]{2,6})",
-        )
-        .expect("Email regex is valid");
+    /// Errors of the checks run after the field checks: the profile, the cross-field rules, the
+    /// ASCII output and the payload length
+    pub(crate) fn payload_errors(&self) -> Vec<SpaydError> {
+        let mut errors = Vec::new();
 
-        // account number
-        if !re_iban.is_match(&self.account) {
-            return Err(SpaydError::InvalidAccountNumber(
-                "Value is not a valid IBAN",
-            ));
+        if let Some(Err(error)) = self.validation.profile.as_ref().map(|p| p.validate(self)) {
+            errors.push(error);
         }
 
-        // amount
-        if self.amount.len() > 10 {
-            return Err(SpaydError::InvalidAmount(
-                "Exceeded maximum length of 10 characters",
-            ));
-        } else if !re_amount.is_match(&self.amount) {
-            return Err(SpaydError::InvalidAmount(
-                "Value is not in a decimal format. Maximum number of decimal places is 2.",
-            ));
+        errors.extend(self.inconsistencies());
+        errors.extend(self.check_ascii().err());
+
+        match self.validation.max_payload_len {
+            Some(max) if self.payload_len() > max => errors.push(SpaydError::PayloadTooLong {
+                len: self.payload_len(),
+                max,
+            }),
+            _ => {}
+        }
+
+        errors
+    }
+
+    /// Whether the payment passes [`Spayd::validate`]
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Validate all fields, returning every error found in payload order
+    ///
+    /// [`Spayd::spayd_string`] stops at the first error instead.
+    pub fn validate_all(&self) -> Result<(), Vec<SpaydError>> {
+        if let Some(spayd) = self.uppercased() {
+            return spayd.validate_all();
         }
 
-        // currency
-        if let Some(ref currency) = self.currency {
-            (TryFrom::try_from(currency.as_str()) as Result<CurrencyCode, ParseCodeError>)
-                .map_err(|_| SpaydError::InvalidCurrency("Invalid currency code"))?;
+        let mut errors = self.fields().errors();
+        errors.extend(self.extras_errors());
+        errors.extend(self.payload_errors());
+
+        if errors.is_empty() {
+            return Ok(());
         }
 
-        // reference
-        if let Some(ref reference) = self.reference {
-            if reference.len() > 16 {
-                return Err(SpaydError::InvalidReference(
-                    "Exceeded maximum length of 16 characters",
-                ));
-            } else if !re_digits.is_match(reference) {
-                return Err(SpaydError::InvalidReference(
-                    "Value contains non-digit characters",
-                ));
-            }
+        errors.sort_by_key(|error| {
+            SpaydKey::ALL
+                .iter()
+                .position(|key| *key == error.key())
+                .unwrap_or(SpaydKey::ALL.len())
+        });
+
+        Err(errors)
+    }
+
+    fn fields(&self) -> Fields<'_> {
+        Fields {
+            account: &self.account,
+            bic: self.bic.as_deref(),
+            alt_accounts: &self.alt_accounts,
+            amount: self.amount.as_deref(),
+            currency: self.currency.as_deref(),
+            reference: self.reference.as_deref(),
+            recipient: self.recipient.as_deref(),
+            date: self.date.as_deref(),
+            payment_type: self.payment_type.as_ref().map(PaymentType::as_str),
+            message: self.message.as_deref(),
+            notify: self.pending_notify.clone().or(self.notify_type()),
+            notify_address: self.notification.as_ref().map(Notification::address),
+            options: &self.validation,
+            amount_format: self.amount_format,
         }
+    }
+}
 
-        // recipient
-        if let Some(ref recipient) = self.recipient {
-            if recipient.len() > 35 {
-                return Err(SpaydError::InvalidRecipient(
-                    "Exceeded maximum length of 35 characters",
-                ));
-            } else if !re_all_allowed.is_match(recipient) {
-                return Err(SpaydError::InvalidRecipient(
-                    "Value contains forbidden character(s)",
-                ));
-            }
+/// Compile a regex once and reuse it on subsequent calls
+macro_rules! regex {
+    ($re:literal, $name:literal) => {{
+        static RE: OnceLock<Regex> = OnceLock::new();
+        RE.get_or_init(|| Regex::new($re).expect(concat!($name, " regex is valid")))
+    }};
+}
+
+/// Check the length and decimal format of an amount
+///
+/// Decimal places are limited by the currency if given (code, minor units), otherwise to two.
+fn check_amount(amount: &str, currency: Option<(&'static str, u32)>) -> Result<(), SpaydError> {
+    let re_amount = regex!(r"^[0-9]+(\.[0-9]+)?$", "Amount");
+
+    let found = amount.split_once('.').map_or(0, |(_, frac)| frac.len());
+
+    if amount.len() > limits::MAX_AMOUNT_LEN {
+        return Err(SpaydError::AmountTooLong {
+            len: amount.len(),
+            max: limits::MAX_AMOUNT_LEN,
+        });
+    } else if !re_amount.is_match(amount) {
+        return Err(SpaydError::AmountNotDecimal);
+    }
+
+    match currency {
+        Some((currency, allowed)) if found > allowed as usize => {
+            Err(SpaydError::TooManyDecimalPlaces { currency, allowed })
         }
+        None if found > 2 => Err(SpaydError::AmountTooManyDecimals { found }),
+        _ => Ok(()),
+    }
+}
 
-        // date
-        if let Some(ref date) = self.date {
-            if !re_date.is_match(date) {
-                return Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format"));
-            }
+/// Borrowed field values, validated the same way for [`Spayd`] and [`SpaydRef`]
+struct Fields<'s> {
+    account: &'s str,
+    bic: Option<&'s str>,
+    alt_accounts: &'s [Account],
+    amount: Option<&'s str>,
+    currency: Option<&'s str>,
+    reference: Option<&'s str>,
+    recipient: Option<&'s str>,
+    date: Option<&'s str>,
+    payment_type: Option<&'s str>,
+    message: Option<&'s str>,
+    notify: Option<NotifyType>,
+    notify_address: Option<&'s str>,
+    options: &'s ValidationOptions,
+    amount_format: AmountFormat,
+}
+
+/// Validation of a single field
+type Check<'s> = fn(&Fields<'s>) -> Result<(), SpaydError>;
+
+impl<'s> Fields<'s> {
+    /// No fields set, for checking a single field
+    fn empty(options: &'s ValidationOptions) -> Self {
+        Fields {
+            account: "",
+            bic: None,
+            alt_accounts: &[],
+            amount: None,
+            currency: None,
+            reference: None,
+            recipient: None,
+            date: None,
+            payment_type: None,
+            message: None,
+            notify: None,
+            notify_address: None,
+            options,
+            amount_format: AmountFormat::AsIs,
         }
+    }
 
-        // payment_type
-        if let Some(ref payment_type) = self.payment_type {
-            if let PaymentType::Other(s) = payment_type {
-                if s.len() > 3 {
-                    return Err(SpaydError::InvalidPaymentType(
-                        "Exceeded maximum length of 3 characters",
-                    ));
-                } else if !re_all_allowed.is_match(s) {
-                    return Err(SpaydError::InvalidPaymentType(
-                        "Value contains forbidden character(s)",
-                    ));
-                }
-            }
+    /// Field checks, in the order errors are reported
+    const CHECKS: [Check<'s>; 10] = [
+        Self::validate_account,
+        Self::validate_alt_accounts,
+        Self::validate_amount,
+        Self::validate_currency,
+        Self::validate_reference,
+        Self::validate_recipient,
+        Self::validate_date,
+        Self::validate_payment_type,
+        Self::validate_message,
+        Self::validate_notify_address,
+    ];
+
+    fn validate(&self) -> Result<(), SpaydError> {
+        Self::CHECKS.iter().try_for_each(|check| check(self))
+    }
+
+    /// Validate a single field
+    fn validate_key(&self, key: &SpaydKey) -> Result<(), SpaydError> {
+        match key {
+            SpaydKey::Acc => self.validate_account(),
+            SpaydKey::AltAcc => self.validate_alt_accounts(),
+            SpaydKey::Am => self.validate_amount(),
+            SpaydKey::Cc => self.validate_currency(),
+            SpaydKey::Rf => self.validate_reference(),
+            SpaydKey::Rn => self.validate_recipient(),
+            SpaydKey::Dt => self.validate_date(),
+            SpaydKey::Pt => self.validate_payment_type(),
+            SpaydKey::Msg => self.validate_message(),
+            SpaydKey::Nt | SpaydKey::Nta => self.validate_notify_address(),
+            SpaydKey::Custom(name) => SpaydKey::validate_custom(name),
         }
+    }
 
-        // message
-        if let Some(ref message) = self.message {
-            if message.len() > 60 {
-                return Err(SpaydError::InvalidMessage(
-                    "Exceeded maximum length of 60 characters",
-                ));
-            } else if !re_all_allowed.is_match(message) {
-                return Err(SpaydError::InvalidRecipient(
-                    "Value contains forbidden character(s)",
-                ));
-            }
+    /// All validation errors, one per invalid field
+    fn errors(&self) -> Vec<SpaydError> {
+        Self::CHECKS
+            .iter()
+            .filter_map(|check| check(self).err())
+            .collect()
+    }
+
+    fn validate_account(&self) -> Result<(), SpaydError> {
+        if self.account.is_empty() && self.bic.is_some() {
+            return Err(SpaydError::InvalidAccountNumber("BIC given without IBAN"));
         }
 
-        // notify (no need to validate)
+        iban::validate(self.account, self.options)?;
 
-        // notify_address
-        if let Some(ref notify_address) = self.notify_address {
-            if notify_address.len() > 320 {
-                return Err(SpaydError::InvalidNotifyAddress(
-                    "Exceeded maximum length of 320 characters",
-                ));
-            }
+        if let Some(bic) = self.bic {
+            let re_bic = regex!(r"^[A-Z0-9]{8}([A-Z0-9]{3})?$", "BIC");
 
-            if let Some(ref notify) = self.notify {
-                match notify {
-                    NotifyType::Phone if !re_phone.is_match(notify_address) => {
-                        return Err(SpaydError::InvalidNotifyAddress("Invalid phone number"));
-                    }
-                    NotifyType::Email if !re_email.is_match(notify_address) => {
-                        return Err(SpaydError::InvalidNotifyAddress("Invalid email address"));
-                    }
-                    _ => {}
-                }
-            } else {
-                return Err(SpaydError::InvalidNotifyAddress(
-                    "Notify type was not provided",
-                ));
+            if !re_bic.is_match(bic) {
+                return Err(SpaydError::InvalidAccountNumber("Value is not a valid BIC"));
             }
         }
 
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
+    fn validate_alt_accounts(&self) -> Result<(), SpaydError> {
+        if self.alt_accounts.len() > limits::MAX_ALT_ACCOUNTS {
+            return Err(SpaydError::InvalidAltAccount(
+                "At most 2 alternative accounts are allowed",
+            ));
+        }
+
+        for (index, account) in self.alt_accounts.iter().enumerate() {
+            if account.iban() == self.account {
+                return Err(SpaydError::InvalidAltAccount(
+                    "Alternative account is the same as ACC",
+                ));
+            }
+
+            Fields {
+                account: account.iban(),
+                bic: account.bic(),
+                ..Fields::empty(self.options)
+            }
+            .validate_account()
+            .map_err(|source| SpaydError::InvalidAltAccountEntry {
+                index,
+                source: Box::new(source),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_amount(&self) -> Result<(), SpaydError> {
+        let Some(amount) = self.amount else {
+            return Ok(());
+        };
+
+        let currency = match self.currency {
+            Some(currency) if self.options.currency_decimals || self.options.strict() => {
+                CurrencyCode::try_from(currency)
+                    .ok()
+                    .and_then(|code| Some((code.alpha(), code.digit()?)))
+            }
+            _ => None,
+        };
+        let decimals = currency.map_or(2, |(_, digit)| digit);
+
+        check_amount(amount, currency)?;
+
+        if self.amount_format == AmountFormat::TwoDecimals {
+            let len = pad_amount(amount).len();
+
+            if len > limits::MAX_AMOUNT_LEN {
+                return Err(SpaydError::AmountTooLong {
+                    len,
+                    max: limits::MAX_AMOUNT_LEN,
+                });
+            }
+        }
+
+        let scale = decimals.max(2);
+        let max = self.options.max_amount.saturating_mul(10u64.pow(scale - 2));
+
+        let minor = match amount_scaled(amount, scale) {
+            Some(minor) if minor <= max => minor,
+            _ => return Err(SpaydError::AmountOutOfRange),
+        };
+
+        if minor == 0 && !self.options.allow_zero_amount {
+            return Err(SpaydError::InvalidAmount(
+                "Amount must be greater than zero",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_currency(&self) -> Result<(), SpaydError> {
+        let currency = match self.currency {
+            Some(currency) => Some(
+                (TryFrom::try_from(currency) as Result<CurrencyCode, ParseCodeError>)
+                    .map_err(|_| SpaydError::InvalidCurrency("Invalid currency code"))?,
+            ),
+            None => self
+                .options
+                .profile
+                .as_ref()
+                .and_then(|profile| profile.default_currency)
+                .or(self.options.default_currency),
+        };
+
+        if let (Some(currency), Some(allowed)) = (currency, &self.options.allowed_currencies) {
+            if !allowed.contains(&currency) {
+                return Err(SpaydError::CurrencyNotAllowed {
+                    currency,
+                    allowed: allowed.clone(),
+                });
+            }
+        }
+
+        if let (Some(currency), true) = (
+            currency,
+            self.options.reject_unusual_currencies || self.options.strict(),
+        ) {
+            currency::check_usual(currency, self.options)?;
+        }
+
+        Ok(())
+    }
+
+    fn validate_reference(&self) -> Result<(), SpaydError> {
+        let re_digits = regex!(r"^[0-9]+$", "Digits-only");
+
+        if let Some(reference) = self.reference {
+            check_len(SpaydKey::Rf, reference, limits::MAX_REFERENCE_DIGITS)?;
+
+            if !re_digits.is_match(reference) {
+                return Err(SpaydError::InvalidReference(
+                    "Value contains non-digit characters",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the length of a value as written with [`EscapePolicy::PercentEncode`]
+    ///
+    /// The raw length is checked by the caller.
+    fn check_encoded_len(&self, key: SpaydKey, value: &str, max: usize) -> Result<(), SpaydError> {
+        if self.options.escape != EscapePolicy::PercentEncode {
+            return Ok(());
+        }
+
+        let len = value.chars().count();
+        let encoded_len = EscapePolicy::PercentEncode.apply(value).chars().count();
+
+        if len <= max && encoded_len > max {
+            return Err(SpaydError::EncodedTooLong {
+                key,
+                len,
+                encoded_len,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn validate_recipient(&self) -> Result<(), SpaydError> {
+        if let Some(recipient) = self.recipient {
+            self.check_encoded_len(SpaydKey::Rn, recipient, limits::MAX_RECIPIENT_CHARS)?;
+            check_len(SpaydKey::Rn, recipient, limits::MAX_RECIPIENT_CHARS)?;
+
+            if self.options.escape == EscapePolicy::Error && !all_allowed(recipient) {
+                return Err(SpaydError::InvalidRecipient(
+                    "Value contains forbidden character(s)",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_date(&self) -> Result<(), SpaydError> {
+        if let Some(date) = self.date {
+            let date: SpaydDate = date.parse()?;
+
+            if self.options.reject_past_dates {
+                let today = self.options.today.unwrap_or_else(SpaydDate::today);
+
+                if date < today {
+                    return Err(SpaydError::DateInPast { date, today });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_payment_type(&self) -> Result<(), SpaydError> {
+        if let Some(s) = self.payment_type {
+            check_len(SpaydKey::Pt, s, limits::MAX_PAYMENT_TYPE_CHARS)?;
+
+            if !s
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            {
+                return Err(SpaydError::InvalidPaymentType(
+                    "Value contains forbidden character(s)",
+                ));
+            } else if (self.options.reject_unknown_payment_types || self.options.strict())
+                && !PaymentType::KNOWN_CODES.contains(&s)
+            {
+                return Err(SpaydError::InvalidPaymentType("Unknown payment type"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_message(&self) -> Result<(), SpaydError> {
+        if let Some(message) = self.message {
+            self.check_encoded_len(SpaydKey::Msg, message, limits::MAX_MESSAGE_CHARS)?;
+            check_len(SpaydKey::Msg, message, limits::MAX_MESSAGE_CHARS)?;
+
+            if self.options.escape == EscapePolicy::Error && !all_allowed(message) {
+                return Err(SpaydError::InvalidMessage(
+                    "Value contains forbidden character(s)",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    // notify (no need to validate)
+
+    fn validate_notify_address(&self) -> Result<(), SpaydError> {
+        // Case-insensitive; the address is written out with its casing unchanged
+        let re_email = regex!(
+            r"(?i)^([a-z0-9_+]([a-z0-9_+.]*[a-z0-9_+])?)@([a-z0-9]+((\.|-+)[a-z0-9]+)*\.([a-z]{2,6}|xn--[a-z0-9]+))$",
+            "Email"
+        );
+
+        if let Some(notify_address) = self.notify_address {
+            check_len(
+                SpaydKey::Nta,
+                notify_address,
+                limits::MAX_NOTIFY_ADDRESS_CHARS,
+            )?;
+
+            if let Some(notify) = &self.notify {
+                let notify_address = notify::written_address(Some(notify), notify_address.into());
+
+                match notify {
+                    NotifyType::Phone => self.validate_phone(&notify_address)?,
+                    NotifyType::Email if !re_email.is_match(&notify_address) => {
+                        return Err(SpaydError::InvalidNotifyAddress("Invalid email address"));
+                    }
+                    NotifyType::Email => check_email_len(&notify_address)?,
+                }
+            } else {
+                return Err(SpaydError::InvalidNotifyAddress(
+                    "Notify type was not provided",
+                ));
+            }
+        } else if self.notify.is_some() {
+            return Err(SpaydError::InvalidNotifyAddress(
+                "Notify address was not provided",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Phone number in international (`+420777123456`) or national (`777123456`) format
+    fn validate_phone(&self, phone: &str) -> Result<(), SpaydError> {
+        let re_phone = regex!(r"^(\+[1-9]|\d)\d*$", "Phone");
+
+        check_len(SpaydKey::Nta, phone, limits::MAX_PHONE_CHARS)?;
+
+        if !re_phone.is_match(phone) {
+            return Err(SpaydError::InvalidNotifyAddress("Invalid phone number"));
+        }
+
+        let digits = phone.trim_start_matches('+').len();
+
+        if digits < self.options.min_phone_digits {
+            Err(SpaydError::InvalidNotifyAddress(
+                "Phone number is too short",
+            ))
+        } else if digits > self.options.max_phone_digits {
+            Err(SpaydError::InvalidNotifyAddress("Phone number is too long"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Check the lengths of an email address as written out, and of its local part and domain
+fn check_email_len(address: &str) -> Result<(), SpaydError> {
+    check_len(SpaydKey::Nta, address, limits::MAX_NOTIFY_ADDRESS_CHARS)?;
+
+    let (local, domain) = address.rsplit_once('@').unwrap_or((address, ""));
+
+    if local.chars().count() > limits::MAX_EMAIL_LOCAL_CHARS {
+        Err(SpaydError::InvalidNotifyAddress(
+            "Local part of the email address exceeds 64 characters",
+        ))
+    } else if domain.chars().count() > limits::MAX_EMAIL_DOMAIN_CHARS {
+        Err(SpaydError::InvalidNotifyAddress(
+            "Domain of the email address exceeds 255 characters",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the length of a value in characters
+fn check_len(key: SpaydKey, value: &str, max: usize) -> Result<(), SpaydError> {
+    let len = value.chars().count();
+
+    if len > max {
+        return Err(SpaydError::TooLong { key, len, max });
+    }
+
+    Ok(())
+}
+
+/// Check that value only contains characters allowed by the specification
+fn all_allowed(value: &str) -> bool {
+    regex!(r"^[0-9A-Z $%+\-./:]+$", "Allowed characters").is_match(value)
+}
+
+#[cfg(test)]
+mod tests {
     use crate::spayd::*;
 
     #[test]
@@ -327,95 +1601,1321 @@ mod tests {
     }
 
     #[test]
-    fn invalid_amount_fails() {
+    fn invalid_account_checksum_fails() {
         let spayd = Spayd::builder()
-            .account("CZ5508000000001234567899".to_string())
-            .amount("239.500".to_string())
+            .account("CZ0000000000000000000000".to_string())
+            .amount("239.50".to_string())
             .build();
 
-        let result = spayd.spayd_string();
-
-        assert!(result.is_err());
         assert_eq!(
-            result,
-            Err(SpaydError::InvalidAmount(
-                "Value is not in a decimal format. Maximum number of decimal places is 2."
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber(
+                "IBAN check digits do not match"
             ))
         );
     }
 
     #[test]
-    fn reference_works() {
+    fn account_is_normalized() {
         let spayd = Spayd::builder()
-            .account("CZ5508000000001234567899".to_string())
+            .account("cz55 0800 0000 0012 3456 7899".to_string())
             .amount("239.50".to_string())
-            .reference("123121".to_string())
             .build();
 
-        let result = spayd.spayd_string();
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ55-0800-0000-0012-3456-7899".to_string())
+            .amount("239.50".to_string())
+            .build();
 
-        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap(),
-            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RF:123121".to_string()
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber(
+                "Value is not a valid IBAN"
+            ))
         );
     }
 
     #[test]
-    fn invalid_reference_fails() {
+    fn invalid_account_length_fails() {
         let spayd = Spayd::builder()
-            .account("CZ5508000000001234567899".to_string())
+            .account("CZ550800000000123456789".to_string())
             .amount("239.50".to_string())
-            .reference("123121123A".to_string())
             .build();
 
         let result = spayd.spayd_string();
 
-        assert!(result.is_err());
         assert_eq!(
             result,
-            Err(SpaydError::InvalidReference(
-                "Value contains non-digit characters"
-            ))
+            Err(SpaydError::InvalidIbanLength {
+                country: "CZ",
+                expected: 24,
+                actual: 23
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid ACC: IBAN for country CZ must have 24 characters, got 23"
         );
     }
 
     #[test]
-    fn recipient_works() {
+    fn unknown_account_country_works() {
+        // "XX" is not in the registry, the check digits are valid
         let spayd = Spayd::builder()
-            .account("CZ5508000000001234567899".to_string())
+            .account("XX6608000000001234567899".to_string())
             .amount("239.50".to_string())
-            .recipient("MISTR1/+.% PO:".to_string())
             .build();
 
-        let result = spayd.spayd_string();
+        assert!(spayd.spayd_string().is_ok());
+
+        let spayd = Spayd::builder()
+            .account("XX6608000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .validation(ValidationOptions {
+                reject_unknown_iban_countries: true,
+                ..Default::default()
+            })
+            .build();
 
-        assert!(result.is_ok());
         assert_eq!(
-            result.unwrap(),
-            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RN:MISTR1/+.% PO:".to_string()
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber(
+                "Unknown IBAN country code"
+            ))
         );
     }
 
     #[test]
-    fn invalid_recipient_fails() {
+    fn allowed_countries_works() {
+        let options = ValidationOptions {
+            allowed_countries: Some(HashSet::from([*b"CZ", *b"SK"])),
+            ..Default::default()
+        };
+
         let spayd = Spayd::builder()
-            .account("CZ5508000000001234567899".to_string())
+            .account("SK3112000000198742637541".to_string())
             .amount("239.50".to_string())
-            .recipient("MISTR1/+*.% PO:".to_string())
+            .validation(options.clone())
+            .build();
+
+        assert!(spayd.spayd_string().is_ok());
+
+        let spayd = Spayd::builder()
+            .account("DE89370400440532013000".to_string())
+            .amount("239.50".to_string())
+            .validation(options)
             .build();
 
         let result = spayd.spayd_string();
 
-        assert!(result.is_err());
         assert_eq!(
             result,
-            Err(SpaydError::InvalidRecipient(
-                "Value contains forbidden character(s)"
+            Err(SpaydError::CountryNotAllowed { country: *b"DE" })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid ACC: Country DE not allowed"
+        );
+    }
+
+    #[test]
+    fn check_czech_account_works() {
+        let options = ValidationOptions {
+            check_czech_account: true,
+            ..Default::default()
+        };
+
+        for account in ["CZ6508000000192000145399", "SK3112000000198742637541"] {
+            let spayd = Spayd::builder()
+                .account(account.to_string())
+                .amount("239.50".to_string())
+                .validation(options.clone())
+                .build();
+
+            assert!(spayd.spayd_string().is_ok());
+        }
+
+        let spayd = Spayd::builder()
+            .account("CZ8207000000001234567890".to_string())
+            .amount("239.50".to_string())
+            .validation(options)
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber(
+                "Czech account number fails modulo-11 check"
             ))
         );
     }
 
+    #[test]
+    fn iban_account_works() {
+        let iban = Iban::parse("CZ65 0800 0000 1920 0014 5399").unwrap();
+
+        let spayd = Spayd::builder()
+            .account(iban.clone())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(spayd.iban(), Ok(iban));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:239.50"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ6508000000192000145398")
+            .amount("239.50".to_string())
+            .build();
+
+        assert!(spayd.iban().is_err());
+    }
+
+    #[test]
+    fn bic_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5855000000001265098001".to_string())
+            .bic("RZBCCZPP".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:239.50"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5855000000001265098001".to_string())
+            .bic("RZBCCZPPXXX".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert!(spayd.spayd_string().is_ok());
+    }
+
+    #[test]
+    fn invalid_bic_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5855000000001265098001".to_string())
+            .bic("RZBCCZP".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber("Value is not a valid BIC"))
+        );
+
+        let spayd = Spayd::builder()
+            .account("".to_string())
+            .bic("RZBCCZPP".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber("BIC given without IBAN"))
+        );
+    }
+
+    #[test]
+    fn without_amount_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("DAR".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:DAR"
+        );
+        assert_eq!(
+            spayd.canonical_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:DAR"
+        );
+    }
+
+    #[test]
+    fn invalid_amount_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.500".to_string())
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert!(result.is_err());
+        assert_eq!(result, Err(SpaydError::AmountTooManyDecimals { found: 3 }));
+
+        for (amount, error) in [
+            (
+                "12345678901",
+                SpaydError::AmountTooLong { len: 11, max: 10 },
+            ),
+            ("239,50", SpaydError::AmountNotDecimal),
+            ("-5", SpaydError::AmountNotDecimal),
+        ] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount(amount.to_string())
+                .build();
+
+            assert_eq!(spayd.spayd_string(), Err(error));
+        }
+    }
+
+    #[test]
+    fn reference_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .reference("123121".to_string())
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RF:123121".to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_reference_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .reference("123121123A".to_string())
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert!(result.is_err());
+        assert_eq!(
+            result,
+            Err(SpaydError::InvalidReference(
+                "Value contains non-digit characters"
+            ))
+        );
+    }
+
+    #[test]
+    fn recipient_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .recipient("MISTR1/+.% PO:".to_string())
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RN:MISTR1/+.% PO:".to_string()
+        );
+    }
+
+    #[test]
+    fn invalid_recipient_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .recipient("MISTR1/+*.% PO:".to_string())
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert!(result.is_err());
+        assert_eq!(
+            result,
+            Err(SpaydError::InvalidRecipient(
+                "Value contains forbidden character(s)"
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_message_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("MISTR1/+.% PO:".to_string())
+            .message("PLATBA*1")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidMessage(
+                "Value contains forbidden character(s)"
+            ))
+        );
+    }
+
+    #[test]
+    fn error_matches_invalid_field() {
+        let builder = || Spayd::builder().account("CZ5508000000001234567899".to_string());
+        let cases = [
+            (
+                SpaydKey::Acc,
+                Spayd::builder()
+                    .account("CZ5508000000001234567898".to_string())
+                    .build(),
+            ),
+            (SpaydKey::Acc, builder().bic("RZBC".to_string()).build()),
+            (SpaydKey::Am, builder().amount("1.234").build()),
+            (SpaydKey::Am, builder().amount("12345678901").build()),
+            (SpaydKey::Cc, builder().currency("XYZ").build()),
+            (SpaydKey::Rf, builder().reference("12A").build()),
+            (SpaydKey::Rf, builder().reference("1".repeat(17)).build()),
+            (SpaydKey::Rn, builder().recipient("A*B".to_string()).build()),
+            (SpaydKey::Rn, builder().recipient("A".repeat(36)).build()),
+            (
+                SpaydKey::Dt,
+                builder().date("2023-8-10".to_string()).build(),
+            ),
+            (
+                SpaydKey::Pt,
+                builder()
+                    .payment_type(PaymentType::Other("A*".to_string()))
+                    .build(),
+            ),
+            (
+                SpaydKey::Pt,
+                builder()
+                    .payment_type(PaymentType::Other("ABCD".to_string()))
+                    .build(),
+            ),
+            (SpaydKey::Msg, builder().message("A*B").build()),
+            (SpaydKey::Msg, builder().message("A".repeat(61)).build()),
+            (SpaydKey::Nta, builder().notify_phone("abc").build()),
+            (
+                SpaydKey::Nta,
+                builder()
+                    .notification(Notification::Email("123".to_string()))
+                    .build(),
+            ),
+        ];
+
+        for (key, spayd) in cases {
+            let error = spayd.spayd_string().unwrap_err();
+
+            assert_eq!(error.key(), key, "{:?}", error);
+
+            let mut valid = builder().build();
+
+            for (key, value) in spayd.iter() {
+                // without a notification, `NT` fails for the missing address
+                let expected = match key {
+                    SpaydKey::Nt => SpaydKey::Nta,
+                    _ => key.clone(),
+                };
+
+                if let Err(error) = valid.set(key, &value) {
+                    assert_eq!(error.key(), expected, "{:?}", error);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn notify_without_address_fails() {
+        let builder = || Spayd::builder().account("CZ5508000000001234567899".to_string());
+
+        assert!(builder().build().spayd_string().is_ok());
+        assert!(builder()
+            .notify_email("email@example.com")
+            .build()
+            .spayd_string()
+            .is_ok());
+
+        let mut spayd = builder().build();
+
+        assert_eq!(
+            spayd.set(SpaydKey::Nta, "email@example.com"),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Notify type was not provided"
+            ))
+        );
+
+        spayd.set(SpaydKey::Nt, "E").unwrap();
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Notify address was not provided"
+            ))
+        );
+        assert_eq!(spayd.notification(), None);
+
+        assert!(matches!(
+            Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*NT:E"),
+            Err(SpaydParseError::InvalidValue {
+                key: SpaydKey::Nta,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn notify_address_trailing_junk_fails() {
+        // set directly, as the builder trims surrounding whitespace
+        let spayd = |notify: NotifyType, address: &str| {
+            let mut spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .build();
+            spayd.notification = Some(Notification::new(notify, address.to_string()));
+            spayd
+        };
+
+        assert_eq!(
+            spayd(NotifyType::Email, "email@example.com*ANYTHING").spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress("Invalid email address"))
+        );
+
+        for address in ["+420123456789*X-VS:1", "+420123456789\n", "123 "] {
+            assert_eq!(
+                spayd(NotifyType::Phone, address).spayd_string(),
+                Err(SpaydError::InvalidNotifyAddress("Invalid phone number")),
+                "{}",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn notify_address_case_is_kept() {
+        let spayd = |address: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .notify_email(address)
+                .build()
+        };
+
+        for address in ["John.Doe@example.com", "john.doe@Example.COM"] {
+            assert_eq!(
+                spayd(address).spayd_string().unwrap(),
+                format!("SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:{}", address)
+            );
+        }
+
+        for address in [
+            "John Doe@Example.com",
+            "John.Doe.Example.com",
+            "John.Doe@Example",
+        ] {
+            assert_eq!(
+                spayd(address).spayd_string(),
+                Err(SpaydError::InvalidNotifyAddress("Invalid email address")),
+                "{}",
+                address
+            );
+        }
+    }
+
+    #[test]
+    fn phone_digits_are_checked() {
+        let spayd = |phone: &str, options: ValidationOptions| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .notify_phone(phone)
+                .validation(options)
+                .build()
+        };
+
+        for phone in ["777123456", "+420777123456", "1234567", "+123456789012345"] {
+            assert!(
+                spayd(phone, Default::default()).spayd_string().is_ok(),
+                "{}",
+                phone
+            );
+        }
+
+        for (phone, message) in [
+            ("5", "Phone number is too short"),
+            ("+123456", "Phone number is too short"),
+            ("1234567890123456", "Phone number is too long"),
+            ("+0420777123456", "Invalid phone number"),
+            ("+", "Invalid phone number"),
+        ] {
+            assert_eq!(
+                spayd(phone, Default::default()).spayd_string(),
+                Err(SpaydError::InvalidNotifyAddress(message)),
+                "{}",
+                phone
+            );
+        }
+
+        let options = ValidationOptions {
+            min_phone_digits: 3,
+            ..Default::default()
+        };
+
+        assert!(spayd("112", options).spayd_string().is_ok());
+    }
+
+    #[test]
+    fn notify_address_limits_work() {
+        let spayd = |notification: Notification| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .notification(notification)
+                .build()
+        };
+
+        assert_eq!(
+            spayd(Notification::Phone("1".repeat(320))).spayd_string(),
+            Err(SpaydError::TooLong {
+                key: SpaydKey::Nta,
+                len: 320,
+                max: limits::MAX_PHONE_CHARS
+            })
+        );
+
+        let email = |local: usize, domain: usize| {
+            Notification::Email(format!(
+                "{}@{}.cz",
+                "a".repeat(local),
+                "b".repeat(domain - 3)
+            ))
+        };
+
+        assert!(spayd(email(64, 255)).spayd_string().is_ok());
+        assert_eq!(
+            spayd(email(65, 20)).spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Local part of the email address exceeds 64 characters"
+            ))
+        );
+        assert_eq!(
+            spayd(email(20, 256)).spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Domain of the email address exceeds 255 characters"
+            ))
+        );
+    }
+
+    #[test]
+    fn validation_levels_work() {
+        let level = |level: ValidationLevel| ValidationOptions {
+            level,
+            ..Default::default()
+        };
+
+        // checked only by Strict: Czech account number, decimals, unknown payment type
+        for spayd in [
+            Spayd::builder()
+                .account("CZ8207000000001234567890".to_string())
+                .build(),
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount("100.50")
+                .currency("JPY")
+                .build(),
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .payment_type(PaymentType::Other("XY".to_string()))
+                .build(),
+        ] {
+            assert!(spayd.spayd_string().is_ok(), "{:?}", spayd);
+            assert!(spayd
+                .spayd_string_with(&level(ValidationLevel::Standard))
+                .is_ok());
+            assert!(spayd
+                .spayd_string_with(&level(ValidationLevel::Strict))
+                .is_err());
+        }
+
+        // repaired only by Lenient
+        let spayd = Spayd::builder()
+            .account("cz55 0800 0000 0012 3456 7899".to_string())
+            .amount("239,50")
+            .message("Faktura č. 1")
+            .notify_phone("+420 777 123 456")
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+        assert!(spayd
+            .spayd_string_with(&level(ValidationLevel::Strict))
+            .is_err());
+        assert_eq!(
+            spayd
+                .spayd_string_with(&level(ValidationLevel::Lenient))
+                .unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:FAKTURA C. 1*NT:P*NTA:+420777123456"
+        );
+
+        // unrecoverable even for Lenient
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567898".to_string())
+            .validation(level(ValidationLevel::Lenient))
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidAccountNumber(
+                "IBAN check digits do not match"
+            ))
+        );
+    }
+
+    #[test]
+    fn is_valid_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        assert!(spayd.is_valid());
+        assert_eq!(spayd.validate(), Ok(()));
+
+        spayd.message = Some("A*B".to_string());
+
+        assert!(!spayd.is_valid());
+        assert_eq!(
+            spayd.validate(),
+            Err(SpaydError::InvalidMessage(
+                "Value contains forbidden character(s)"
+            ))
+        );
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn qrcode_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50")
+            .build();
+
+        assert!(spayd.qrcode().is_ok());
+
+        spayd.amount = Some("1.234".to_string());
+
+        assert!(matches!(
+            spayd.qrcode(),
+            Err(SpaydQrError::InvalidPayment(
+                SpaydError::AmountTooManyDecimals { found: 3 }
+            ))
+        ));
+
+        spayd.amount = Some("239.50".to_string());
+        spayd.validation.max_payload_len = None;
+        spayd.extras = vec![("X-ID".to_string(), "A".repeat(5000))];
+
+        assert!(matches!(spayd.qrcode(), Err(SpaydQrError::QrCode(_))));
+    }
+
+    #[test]
+    fn invalid_extra_key_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .extras(vec![
+                ("".to_string(), "v".to_string()),
+                ("MSG".to_string(), "dup".to_string()),
+                ("X-VS".to_string(), "12".to_string()),
+            ])
+            .build();
+        let empty = || SpaydError::InvalidCustomKey {
+            key: SpaydKey::Custom(String::new()),
+            reason: "Name is empty",
+        };
+
+        assert_eq!(spayd.spayd_string(), Err(empty()));
+        assert_eq!(
+            spayd.validate_all(),
+            Err(vec![
+                empty(),
+                SpaydError::InvalidCustomKey {
+                    key: SpaydKey::Custom("MSG".to_string()),
+                    reason: "Name of a field modelled by this crate",
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn validate_all_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("A*B")
+            .amount("1,5")
+            .reference("12345678901")
+            .validation(ValidationOptions {
+                profile: Some(ValidationProfile::AIRBANK),
+                ..Default::default()
+            })
+            .build();
+
+        let errors = || {
+            vec![
+                SpaydError::AmountNotDecimal,
+                SpaydError::RejectedByProfile {
+                    key: SpaydKey::Rf,
+                    len: 11,
+                    max: 10,
+                },
+                SpaydError::InvalidMessage("Value contains forbidden character(s)"),
+            ]
+        };
+
+        assert_eq!(spayd.validate_all(), Err(errors()));
+        assert_eq!(spayd.spayd_string_report(), Err(errors()));
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(errors().into_iter().next().unwrap())
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        assert_eq!(spayd.validate_all(), Ok(()));
+        assert_eq!(
+            spayd.spayd_string_report().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899"
+        );
+    }
+
+    #[test]
+    fn lengths_are_counted_in_characters() {
+        let builder = || Spayd::builder().account("CZ5508000000001234567899".to_string());
+        let spayd = |key: &SpaydKey, value: String| match key {
+            SpaydKey::Rf => builder().reference(value).build(),
+            SpaydKey::Rn => builder().recipient(value).build(),
+            SpaydKey::Pt => builder().payment_type(PaymentType::Other(value)).build(),
+            SpaydKey::Msg => builder().message(value).build(),
+            _ => builder().notify_email(value).build(),
+        };
+
+        for (key, max) in [
+            (SpaydKey::Rf, limits::MAX_REFERENCE_DIGITS),
+            (SpaydKey::Rn, limits::MAX_RECIPIENT_CHARS),
+            (SpaydKey::Pt, limits::MAX_PAYMENT_TYPE_CHARS),
+            (SpaydKey::Msg, limits::MAX_MESSAGE_CHARS),
+            (SpaydKey::Nta, limits::MAX_NOTIFY_ADDRESS_CHARS),
+        ] {
+            let error = spayd(&key, "Č".repeat(max)).spayd_string().unwrap_err();
+            assert!(!matches!(error, SpaydError::TooLong { .. }), "{}", key);
+
+            let error = spayd(&key, "Č".repeat(max + 1)).spayd_string().unwrap_err();
+            assert_eq!(
+                error,
+                SpaydError::TooLong {
+                    key: key.clone(),
+                    len: max + 1,
+                    max
+                }
+            );
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "invalid {}: Exceeded maximum length of {} characters, got {}",
+                    key,
+                    max,
+                    max + 1
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn payload_too_long_fails() {
+        let extras = (0..20)
+            .map(|i| (format!("X-NOTE{}", i), "LOREM IPSUM DOLOR".to_string()))
+            .collect();
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50")
+            .message("A".repeat(60))
+            .extras(extras)
+            .build();
+
+        let result = spayd.spayd_string();
+
+        assert_eq!(spayd.payload_len(), 641);
+        assert_eq!(
+            result,
+            Err(SpaydError::PayloadTooLong { len: 641, max: 600 })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid payload: Payload has 641 bytes, exceeding the maximum of 600 bytes"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("A".repeat(60))
+            .validation(ValidationOptions {
+                max_payload_len: Some(100),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(spayd.payload_len(), 101);
+        assert!(spayd.spayd_string().is_err());
+    }
+
+    #[test]
+    fn escape_policy_works() {
+        let options = ValidationOptions {
+            escape: EscapePolicy::PercentEncode,
+            ..Default::default()
+        };
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("NOVÁČEK".to_string())
+            .message("A*B")
+            .validation(options.clone())
+            .build();
+
+        let result = spayd.spayd_string().unwrap();
+
+        assert_eq!(
+            result,
+            "SPD*1.0*ACC:CZ5508000000001234567899*RN:NOV%C3%81%C4%8CEK*MSG:A%2AB"
+        );
+
+        let parsed = Spayd::parse_with(
+            &result,
+            &ParseOptions {
+                validation: options,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(parsed.message.as_deref(), Some("A*B"));
+        assert_eq!(parsed.recipient.as_deref(), Some("NOVÁČEK"));
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("A*B č")
+            .validation(ValidationOptions {
+                escape: EscapePolicy::Strip,
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:AB "
+        );
+    }
+
+    #[test]
+    fn default_escape_round_trip_works() {
+        for value in ["SLEVA 10%20", "%2A", "100%FF", "ABC%AB", "10 % OFF"] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .recipient(value.to_string())
+                .message(value)
+                .build();
+
+            let parsed = Spayd::parse(&spayd.spayd_string().unwrap()).unwrap();
+
+            assert_eq!(parsed.recipient.as_deref(), Some(value));
+            assert_eq!(parsed.message.as_deref(), Some(value));
+        }
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("SLEVA 10%20".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*RN:SLEVA 10%2520"
+        );
+    }
+
+    #[test]
+    fn percent_encoding_round_trip_works() {
+        let options = ValidationOptions {
+            escape: EscapePolicy::PercentEncode,
+            ..Default::default()
+        };
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("SLEVA 10 %2A")
+            .extras(vec![("X-NOTE".to_string(), "100 % *\t".to_string())])
+            .validation(options.clone())
+            .build();
+
+        let result = spayd.spayd_string().unwrap();
+
+        assert_eq!(
+            result,
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:SLEVA 10 %252A*X-NOTE:100 %25 %2A%09"
+        );
+
+        let parsed = Spayd::parse_with(
+            &result,
+            &ParseOptions {
+                validation: options,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(parsed.message.as_deref(), Some("SLEVA 10 %2A"));
+        assert_eq!(parsed.extra("X-NOTE"), Some("100 % *\t"));
+        assert_eq!(parsed.spayd_string().unwrap(), result);
+    }
+
+    #[test]
+    fn encoded_length_is_checked() {
+        let spayd = |message: String| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .message(message)
+                .validation(ValidationOptions {
+                    escape: EscapePolicy::PercentEncode,
+                    ..Default::default()
+                })
+                .build()
+                .spayd_string()
+        };
+
+        assert!(spayd(format!("{}**", "A".repeat(54))).is_ok());
+
+        let result = spayd(format!("{}ŽŽ", "A".repeat(56)));
+
+        assert_eq!(
+            result,
+            Err(SpaydError::EncodedTooLong {
+                key: SpaydKey::Msg,
+                len: 58,
+                encoded_len: 68,
+                max: 60
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid MSG: Exceeded maximum length of 60 characters, got 58 characters (68 percent-encoded)"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient(format!("{}%", "A".repeat(33)))
+            .validation(ValidationOptions {
+                escape: EscapePolicy::PercentEncode,
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(spayd.spayd_string().unwrap_err().key(), SpaydKey::Rn);
+    }
+
+    #[test]
+    fn unchecked_string_can_be_split() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("A*B")
+            .reference("12".to_string())
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+
+        let result = spayd.spayd_string_unchecked();
+        let options = ParseOptions {
+            validation: ValidationOptions {
+                escape: EscapePolicy::PercentEncode,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parsed = Spayd::parse_with(&result, &options).unwrap();
+
+        assert_eq!(
+            result,
+            "SPD*1.0*ACC:CZ5508000000001234567899*RF:12*MSG:A%2AB"
+        );
+        assert_eq!(parsed.message.as_deref(), Some("A*B"));
+        assert_eq!(parsed.reference.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_trimmed() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899 ".to_string())
+            .amount("239.50 ")
+            .currency("CZK\u{A0}")
+            .recipient(" JAN  NOVAK\t")
+            .date(" 20240601")
+            .message("PLATBA ZA ZBOZI ")
+            .notify_email("jan@example.com\n")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*CC:CZK*RN:JAN  NOVAK*DT:20240601*\
+             MSG:PLATBA ZA ZBOZI*NT:E*NTA:jan@example.com"
+        );
+
+        let mut spayd = spayd;
+        spayd.set(SpaydKey::Rf, "1234 ").unwrap();
+        spayd.set(SpaydKey::Am, "\u{A0}100").unwrap();
+
+        assert_eq!(spayd.get(SpaydKey::Rf).as_deref(), Some("1234"));
+        assert_eq!(spayd.get(SpaydKey::Am).as_deref(), Some("100"));
+    }
+
+    #[test]
+    fn require_ascii_payload_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("PLATBA")
+            .extras(vec![("X-ID".to_string(), "OBJEDNÁVKA 12".to_string())])
+            .build();
+
+        assert!(spayd.spayd_string().is_ok());
+
+        spayd.validation.require_ascii_payload = true;
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::NonAsciiPayload {
+                key: SpaydKey::Custom("X-ID".to_string()),
+                character: 'Á'
+            })
+        );
+        assert_eq!(
+            spayd.validate().unwrap_err().to_string(),
+            "invalid X-ID: Value contains non-ASCII character 'Á' (U+00C1)"
+        );
+
+        spayd.validation.escape = EscapePolicy::PercentEncode;
+        spayd.set(SpaydKey::Msg, "PLATBA ZA ZBOŽÍ").unwrap();
+        spayd.unset(SpaydKey::Custom("X-ID".to_string())).unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:PLATBA ZA ZBO%C5%BD%C3%8D"
+        );
+    }
+
+    #[test]
+    fn field_order_works() {
+        let spayd = |field_order: FieldOrder| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount("239.50")
+                .currency("CZK")
+                .reference("1234")
+                .message("PLATBA")
+                .extras(vec![
+                    ("X-VS".to_string(), "1234".to_string()),
+                    ("X-KS".to_string(), "0308".to_string()),
+                    ("DL".to_string(), "1".to_string()),
+                ])
+                .field_order(field_order)
+                .build()
+        };
+
+        assert_eq!(
+            spayd(FieldOrder::Conventional).spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*CC:CZK*RF:1234*MSG:PLATBA\
+             *X-VS:1234*X-KS:0308*DL:1"
+        );
+
+        let canonical = spayd(FieldOrder::SpecCanonical);
+
+        assert_eq!(
+            canonical.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*CC:CZK*DL:1*MSG:PLATBA\
+             *RF:1234*X-KS:0308*X-VS:1234"
+        );
+        assert_eq!(
+            canonical.spayd_string().unwrap(),
+            spayd(FieldOrder::Conventional).canonical_string().unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_string_works() {
+        let a = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("5".to_string())
+            .message("10 % OFF".to_string())
+            .currency("CZK".to_string())
+            .build();
+
+        let b = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("005.00".to_string())
+            .currency("CZK".to_string())
+            .message("10 % OFF".to_string())
+            .build();
+
+        assert_eq!(a.canonical_string(), b.canonical_string());
+        assert_eq!(
+            a.canonical_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5.00*CC:CZK*MSG:10 %25 OFF".to_string()
+        );
+    }
+
+    #[test]
+    fn max_amount_works() {
+        for (amount, valid) in [
+            ("9999999.99", true),
+            ("99999999.9", false),
+            ("0000000001", true),
+        ] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount(amount.to_string())
+                .build();
+
+            assert_eq!(spayd.spayd_string().is_ok(), valid, "{}", amount);
+        }
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("1000.01".to_string())
+            .validation(ValidationOptions {
+                max_amount: 100_000,
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(spayd.spayd_string(), Err(SpaydError::AmountOutOfRange));
+    }
+
+    #[test]
+    fn allow_zero_amount_works() {
+        let spayd = |amount: &str, allow_zero_amount| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount(amount.to_string())
+                .validation(ValidationOptions {
+                    allow_zero_amount,
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        for amount in ["0", "0.0", "0.00", "00"] {
+            assert!(spayd(amount, true).spayd_string().is_ok());
+            assert_eq!(
+                spayd(amount, false).spayd_string(),
+                Err(SpaydError::InvalidAmount(
+                    "Amount must be greater than zero"
+                ))
+            );
+        }
+
+        assert!(spayd("0.01", false).spayd_string().is_ok());
+    }
+
+    #[test]
+    fn currency_decimals_works() {
+        let spayd = |amount: &str, currency: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount(amount.to_string())
+                .currency(currency.to_string())
+                .validation(ValidationOptions {
+                    currency_decimals: true,
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        assert!(spayd("100", "JPY").spayd_string().is_ok());
+        assert!(spayd("100.50", "CZK").spayd_string().is_ok());
+        assert!(spayd("100.505", "BHD").spayd_string().is_ok());
+        assert!(spayd("9999999.99", "BHD").spayd_string().is_ok());
+
+        let result = spayd("100.50", "JPY").spayd_string();
+
+        assert_eq!(
+            result,
+            Err(SpaydError::TooManyDecimalPlaces {
+                currency: "JPY",
+                allowed: 0
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid AM: JPY does not allow decimal places"
+        );
+        assert_eq!(
+            spayd("100.5055", "BHD")
+                .spayd_string()
+                .unwrap_err()
+                .to_string(),
+            "invalid AM: BHD allows at most 3 decimal places"
+        );
+        assert_eq!(
+            spayd("100.505", "CZK").spayd_string(),
+            Err(SpaydError::TooManyDecimalPlaces {
+                currency: "CZK",
+                allowed: 2
+            })
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("100.505".to_string())
+            .currency("BHD".to_string())
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+    }
+
+    #[test]
+    fn amount_format_works() {
+        let spayd = |amount: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount(amount.to_string())
+                .amount_format(AmountFormat::TwoDecimals)
+                .build()
+        };
+
+        assert_eq!(
+            spayd("5").spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5.00"
+        );
+        assert_eq!(
+            spayd("5.1").spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5.10"
+        );
+        assert_eq!(
+            spayd("5.25").spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5.25"
+        );
+        assert_eq!(
+            spayd("12345678").spayd_string(),
+            Err(SpaydError::AmountTooLong { len: 11, max: 10 })
+        );
+    }
+
+    #[test]
+    fn amount_minor_works() {
+        assert_eq!(amount_minor("239.50"), Some(23950));
+        assert_eq!(amount_minor("5"), Some(500));
+        assert_eq!(amount_minor("0.5"), Some(50));
+        assert_eq!(amount_minor("9999999999"), Some(999_999_999_900));
+        assert_eq!(amount_minor("5."), None);
+        assert_eq!(amount_minor("+5"), None);
+        assert_eq!(amount_minor("5.123"), None);
+    }
+
+    #[test]
+    fn canonical_amount_works() {
+        assert_eq!(canonical_amount("5"), "5.00");
+        assert_eq!(canonical_amount("5.1"), "5.10");
+        assert_eq!(canonical_amount("0.5"), "0.50");
+        assert_eq!(canonical_amount("000"), "0.00");
+        assert_eq!(canonical_amount("120.25"), "120.25");
+    }
+
     #[test]
     fn full_works() {
         let spayd = Spayd::builder()
@@ -427,8 +2927,7 @@ mod tests {
             .date("20230810".to_string())
             .payment_type(PaymentType::Instant)
             .message("PAYMENT".to_string())
-            .notify(NotifyType::Email)
-            .notify_address("email@example.com".to_string())
+            .notify_email("email@example.com")
             .build();
 
         let result = spayd.spayd_string();
@@ -0,0 +1,331 @@
+use std::borrow::Cow;
+
+use super::*;
+
+impl Spayd {
+    /// Raw value of a field, if set
+    ///
+    /// Payment and notify types are rendered to their codes (e.g. `IP`, `E`), the account
+    /// includes the BIC if set (`IBAN+BIC`).
+    pub fn get(&self, key: SpaydKey) -> Option<Cow<'_, str>> {
+        let value = match key {
+            SpaydKey::Acc => match self.bic {
+                Some(ref bic) => return Some(Cow::Owned(format!("{}+{}", self.account, bic))),
+                None => Some(self.account.as_str()),
+            },
+            SpaydKey::AltAcc if self.alt_accounts.is_empty() => None,
+            SpaydKey::AltAcc => return Some(Cow::Owned(account::write_list(&self.alt_accounts))),
+            SpaydKey::Am => self.amount.as_deref(),
+            SpaydKey::Cc => self.currency.as_deref(),
+            SpaydKey::Rf => self.reference.as_deref(),
+            SpaydKey::Rn => self.recipient.as_deref(),
+            SpaydKey::Dt => self.date.as_deref(),
+            SpaydKey::Pt => self.payment_type.as_ref().map(PaymentType::as_str),
+            SpaydKey::Msg => self.message.as_deref(),
+            SpaydKey::Nt => self
+                .pending_notify
+                .clone()
+                .or(self.notify_type())
+                .map(|notify| notify.as_str()),
+            SpaydKey::Nta => self.notification.as_ref().map(Notification::address),
+            SpaydKey::Custom(ref key) => self.extra(key),
+        };
+
+        value.map(Cow::Borrowed)
+    }
+
+    /// Raw value of a field by its SPAYD name (e.g. `MSG`, `X-VS`), if set
+    ///
+    /// Names are matched case-insensitively.
+    pub fn get_str(&self, key: &str) -> Option<Cow<'_, str>> {
+        self.get(key.parse().ok()?)
+    }
+}
+
+impl Spayd {
+    /// Set a field from its raw value (e.g. `IP` for the payment type)
+    ///
+    /// Surrounding whitespace is trimmed. Only the changed field is validated; the value is not
+    /// stored if it's invalid. `NT` keeps the address if it's valid for the new type, otherwise
+    /// the type waits for `NTA`, and the payment doesn't validate until then.
+    pub fn set(&mut self, key: SpaydKey, value: &str) -> Result<(), SpaydError> {
+        let value = text::trim(value);
+        let (value, bic) = match key {
+            SpaydKey::Acc => iban::split_bic(iban::normalize(value.into())),
+            SpaydKey::Cc => (currency::normalize(value.into()), None),
+            SpaydKey::Dt => (date::normalize(value.into()), None),
+            SpaydKey::Pt => (payment_type::normalize(value.into()), None),
+            SpaydKey::Rn | SpaydKey::Msg => (text::compose(value.into()), None),
+            _ => (Cow::Borrowed(value), None),
+        };
+        let value = value.as_ref();
+
+        let notify = match key {
+            SpaydKey::Nt => Some(value.parse::<NotifyType>()?),
+            _ => None,
+        };
+        let alt_accounts = match key {
+            SpaydKey::AltAcc => account::parse_list(value),
+            _ => Vec::new(),
+        };
+
+        let mut fields = self.fields();
+
+        match key {
+            SpaydKey::Acc => {
+                fields.account = value;
+                fields.bic = bic.as_deref();
+            }
+            SpaydKey::AltAcc => fields.alt_accounts = &alt_accounts,
+            SpaydKey::Am => fields.amount = Some(value),
+            SpaydKey::Cc => fields.currency = Some(value),
+            SpaydKey::Rf => fields.reference = Some(value),
+            SpaydKey::Rn => fields.recipient = Some(value),
+            SpaydKey::Dt => fields.date = Some(value),
+            SpaydKey::Pt => fields.payment_type = Some(value),
+            SpaydKey::Msg => fields.message = Some(value),
+            SpaydKey::Nt => fields.notify = notify.clone(),
+            SpaydKey::Nta => fields.notify_address = Some(value),
+            SpaydKey::Custom(_) => {}
+        }
+
+        match key {
+            // the address is kept if it's valid for the new type, otherwise the type waits for one
+            SpaydKey::Nt if fields.validate_key(&key).is_err() => fields.notify_address = None,
+            SpaydKey::Nt => {}
+            _ => fields.validate_key(&key)?,
+        }
+
+        // both halves are set once the notification passed validation
+        let notification = fields
+            .notify
+            .zip(fields.notify_address)
+            .map(|(notify, address)| Notification::new(notify, address.to_string()));
+        let value = value.to_string();
+
+        match key {
+            SpaydKey::Acc => {
+                self.account = value;
+                self.bic = bic.map(Cow::into_owned);
+            }
+            SpaydKey::AltAcc => self.alt_accounts = alt_accounts,
+            SpaydKey::Am => self.amount = Some(value),
+            SpaydKey::Cc => self.currency = Some(value),
+            SpaydKey::Rf => self.reference = Some(value),
+            SpaydKey::Rn => self.recipient = Some(value),
+            SpaydKey::Dt => self.date = Some(value),
+            SpaydKey::Pt => self.payment_type = Some(PaymentType::from_code(&value)),
+            SpaydKey::Msg => self.message = Some(value),
+            SpaydKey::Nt | SpaydKey::Nta => {
+                self.pending_notify = match notification {
+                    Some(_) => None,
+                    None => notify,
+                };
+                self.notification = notification;
+            }
+            SpaydKey::Custom(key) => match self.extras.iter_mut().find(|(k, _)| *k == key) {
+                Some(extra) => extra.1 = value,
+                None => self.extras.push((key, value)),
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Unset an optional field
+    ///
+    /// The mandatory account (`ACC`) cannot be unset; unsetting `NT` or `NTA` removes the whole
+    /// notification.
+    pub fn unset(&mut self, key: SpaydKey) -> Result<(), SpaydError> {
+        match key {
+            SpaydKey::Acc => {
+                return Err(SpaydError::InvalidAccountNumber(
+                    "Mandatory field cannot be unset",
+                ))
+            }
+            SpaydKey::AltAcc => self.alt_accounts.clear(),
+            SpaydKey::Am => self.amount = None,
+            SpaydKey::Cc => self.currency = None,
+            SpaydKey::Rf => self.reference = None,
+            SpaydKey::Rn => self.recipient = None,
+            SpaydKey::Dt => self.date = None,
+            SpaydKey::Pt => self.payment_type = None,
+            SpaydKey::Msg => self.message = None,
+            SpaydKey::Nt | SpaydKey::Nta => {
+                self.notification = None;
+                self.pending_notify = None;
+            }
+            SpaydKey::Custom(key) => self.extras.retain(|(k, _)| *k != key),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn get_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .payment_type(PaymentType::Instant)
+            .notify_phone("+420123456789")
+            .extras(vec![("X-VS".to_string(), "1234".to_string())])
+            .build();
+
+        assert_eq!(spayd.get(SpaydKey::Am).as_deref(), Some("239.50"));
+        assert_eq!(spayd.get(SpaydKey::Pt).as_deref(), Some("IP"));
+        assert_eq!(spayd.get(SpaydKey::Nt).as_deref(), Some("P"));
+        assert_eq!(spayd.get(SpaydKey::Msg), None);
+
+        assert_eq!(spayd.get_str("nta").as_deref(), Some("+420123456789"));
+        assert_eq!(spayd.get_str("X-VS").as_deref(), Some("1234"));
+        assert_eq!(spayd.get_str("MSG"), None);
+        assert_eq!(spayd.get_str("x-vs").as_deref(), Some("1234"));
+        assert_eq!(
+            spayd.get(SpaydKey::Custom("X-VS".to_string())).as_deref(),
+            Some("1234")
+        );
+        assert_eq!(spayd.get_str("FOO"), None);
+        assert_eq!(spayd.get_str(""), None);
+    }
+
+    #[test]
+    fn set_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        spayd
+            .set(SpaydKey::Acc, "cz65 0800 0000 1920 0014 5399")
+            .unwrap();
+        spayd.set(SpaydKey::Am, "100").unwrap();
+        spayd.set(SpaydKey::Pt, "IP").unwrap();
+        spayd.set(SpaydKey::Nt, "E").unwrap();
+        spayd.set(SpaydKey::Nta, "email@example.com").unwrap();
+
+        assert!(matches!(spayd.payment_type, Some(PaymentType::Instant)));
+        assert_eq!(spayd.notify_type(), Some(NotifyType::Email));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:100*PT:IP*NT:E*NTA:email@example.com"
+        );
+    }
+
+    #[test]
+    fn set_account_with_bic_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        spayd
+            .set(SpaydKey::Acc, "CZ5855000000001265098001+RZBCCZPP")
+            .unwrap();
+        assert_eq!(spayd.bic.as_deref(), Some("RZBCCZPP"));
+        assert_eq!(
+            spayd.get(SpaydKey::Acc).as_deref(),
+            Some("CZ5855000000001265098001+RZBCCZPP")
+        );
+
+        assert!(spayd
+            .set(SpaydKey::Acc, "CZ5855000000001265098001+RZBCCZP")
+            .is_err());
+        assert_eq!(spayd.bic.as_deref(), Some("RZBCCZPP"));
+
+        spayd
+            .set(SpaydKey::Acc, "CZ5508000000001234567899")
+            .unwrap();
+        assert_eq!(spayd.bic, None);
+    }
+
+    #[test]
+    fn set_invalid_value_fails() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.set(SpaydKey::Dt, "2023-8-10"),
+            Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format"))
+        );
+        assert_eq!(
+            spayd.set(SpaydKey::Nt, "X"),
+            Err(SpaydError::InvalidNotifyType("Value is not P or E"))
+        );
+        assert_eq!(
+            spayd.set(SpaydKey::Nta, "+420123456789"),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Notify type was not provided"
+            ))
+        );
+        assert_eq!(spayd.date, None);
+        assert!(spayd.notification().is_none());
+    }
+
+    #[test]
+    fn set_invalid_custom_key_fails() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        for (name, reason) in [
+            ("", "Name is empty"),
+            ("X-A*B", "Name contains * or :"),
+            ("X-A:B", "Name contains * or :"),
+            ("MSG", "Name of a field modelled by this crate"),
+            ("nta", "Name of a field modelled by this crate"),
+        ] {
+            assert_eq!(
+                spayd.set(SpaydKey::Custom(name.to_string()), "1"),
+                Err(SpaydError::InvalidCustomKey {
+                    key: SpaydKey::Custom(name.to_string()),
+                    reason
+                })
+            );
+        }
+
+        spayd
+            .set(SpaydKey::Custom("X-VS".to_string()), "1")
+            .unwrap();
+        assert_eq!(spayd.extras, [("X-VS".to_string(), "1".to_string())]);
+    }
+
+    #[test]
+    fn unset_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .message("PAYMENT".to_string())
+            .build();
+
+        spayd
+            .set(SpaydKey::Custom("X-VS".to_string()), "1")
+            .unwrap();
+        spayd
+            .set(SpaydKey::Custom("X-VS".to_string()), "2")
+            .unwrap();
+
+        assert_eq!(spayd.extras(), [("X-VS".to_string(), "2".to_string())]);
+
+        spayd.unset(SpaydKey::Msg).unwrap();
+        spayd.unset(SpaydKey::Custom("X-VS".to_string())).unwrap();
+
+        assert_eq!(spayd.message, None);
+        assert!(spayd.extras().is_empty());
+        assert_eq!(
+            spayd.unset(SpaydKey::Acc),
+            Err(SpaydError::InvalidAccountNumber(
+                "Mandatory field cannot be unset"
+            ))
+        );
+
+        spayd.unset(SpaydKey::Am).unwrap();
+        assert_eq!(spayd.get(SpaydKey::Am), None);
+    }
+}
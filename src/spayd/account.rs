@@ -0,0 +1,220 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+/// Account given by its IBAN, optionally with the BIC of the bank, as listed in `ALT-ACC`
+///
+/// The IBAN is normalized to the electronic format; it's validated with the payment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    iban: String,
+    bic: Option<String>,
+}
+
+impl Account {
+    /// Account with the given IBAN (`CZ55 0800 ...` becomes `CZ550800...`)
+    pub fn new(iban: impl Into<String>) -> Self {
+        Account {
+            iban: iban::normalize(iban.into().into()).into_owned(),
+            bic: None,
+        }
+    }
+
+    /// Add the BIC of the bank, written after the IBAN as `IBAN+BIC`
+    pub fn with_bic(mut self, bic: impl Into<String>) -> Self {
+        self.bic = Some(bic.into());
+        self
+    }
+
+    /// IBAN in the electronic format
+    pub fn iban(&self) -> &str {
+        &self.iban
+    }
+
+    /// BIC of the bank, if given
+    pub fn bic(&self) -> Option<&str> {
+        self.bic.as_deref()
+    }
+
+    fn from_written(s: &str) -> Self {
+        let (iban, bic) = iban::split_bic(iban::normalize(s.into()));
+
+        Account {
+            iban: iban.into_owned(),
+            bic: bic.map(Cow::into_owned),
+        }
+    }
+}
+
+impl From<Iban> for Account {
+    fn from(iban: Iban) -> Self {
+        Account::new(iban)
+    }
+}
+
+impl FromStr for Account {
+    type Err = Infallible;
+
+    /// Read an account written as `IBAN` or `IBAN+BIC`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Account::from_written(s))
+    }
+}
+
+impl fmt::Display for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.iban)?;
+
+        if let Some(bic) = &self.bic {
+            write!(f, "+{}", bic)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Spayd {
+    /// Alternative accounts (`ALT-ACC`) the payer may send the payment to instead
+    pub fn alt_accounts(&self) -> &[Account] {
+        &self.alt_accounts
+    }
+}
+
+/// Read a comma-separated list of accounts, as written in `ALT-ACC`
+pub(crate) fn parse_list(value: &str) -> Vec<Account> {
+    if value.is_empty() {
+        return Vec::new();
+    }
+
+    value.split(',').map(Account::from_written).collect()
+}
+
+/// Write accounts as a comma-separated list, as in `ALT-ACC`
+pub(crate) fn write_list(accounts: &[Account]) -> String {
+    accounts
+        .iter()
+        .map(Account::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    fn spayd(alt_accounts: Vec<Account>) -> Spayd {
+        Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .alt_accounts(alt_accounts)
+            .amount("100")
+            .build()
+    }
+
+    #[test]
+    fn account_works() {
+        let account = Account::new("cz65 0800 0000 1920 0014 5399").with_bic("GIBACZPX");
+
+        assert_eq!(account.iban(), "CZ6508000000192000145399");
+        assert_eq!(account.bic(), Some("GIBACZPX"));
+        assert_eq!(account.to_string(), "CZ6508000000192000145399+GIBACZPX");
+        assert_eq!(
+            "CZ6508000000192000145399+GIBACZPX".parse::<Account>(),
+            Ok(account)
+        );
+    }
+
+    #[test]
+    fn alt_accounts_work() {
+        let spayd = spayd(vec![
+            Account::new("CZ6508000000192000145399"),
+            Account::new("CZ2601000000195505000000").with_bic("KOMBCZPP"),
+        ]);
+        let expected = "SPD*1.0*ACC:CZ5508000000001234567899\
+                        *ALT-ACC:CZ6508000000192000145399,CZ2601000000195505000000+KOMBCZPP\
+                        *AM:100";
+
+        assert_eq!(spayd.spayd_string().unwrap(), expected);
+        assert_eq!(
+            spayd.get(SpaydKey::AltAcc).as_deref(),
+            Some("CZ6508000000192000145399,CZ2601000000195505000000+KOMBCZPP")
+        );
+
+        let parsed = Spayd::parse(expected).unwrap();
+
+        assert_eq!(parsed.alt_accounts(), spayd.alt_accounts());
+        assert_eq!(parsed.spayd_string().unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_alt_accounts_fail() {
+        let accounts = [
+            "CZ6508000000192000145399",
+            "CZ2601000000195505000000",
+            "SK3112000000198742637541",
+        ];
+
+        assert_eq!(
+            spayd(accounts.iter().map(|a| Account::new(*a)).collect()).spayd_string(),
+            Err(SpaydError::InvalidAltAccount(
+                "At most 2 alternative accounts are allowed"
+            ))
+        );
+        assert_eq!(
+            spayd(vec![Account::new("CZ5508000000001234567899")]).spayd_string(),
+            Err(SpaydError::InvalidAltAccount(
+                "Alternative account is the same as ACC"
+            ))
+        );
+
+        let result = spayd(vec![
+            Account::new("CZ6508000000192000145399"),
+            Account::new("CZ6508000000192000145398"),
+        ])
+        .spayd_string();
+
+        assert_eq!(
+            result,
+            Err(SpaydError::InvalidAltAccountEntry {
+                index: 1,
+                source: Box::new(SpaydError::InvalidAccountNumber(
+                    "IBAN check digits do not match"
+                ))
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid ALT-ACC: Entry 2: IBAN check digits do not match"
+        );
+
+        let input = "SPD*1.0*ACC:CZ5508000000001234567899*ALT-ACC:CZ65080000,X";
+
+        assert!(matches!(
+            Spayd::parse(input),
+            Err(SpaydParseError::InvalidValue {
+                key: SpaydKey::AltAcc,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn set_alt_accounts_works() {
+        let mut spayd = spayd(Vec::new());
+
+        assert_eq!(spayd.get(SpaydKey::AltAcc), None);
+
+        spayd
+            .set(SpaydKey::AltAcc, "CZ6508000000192000145399")
+            .unwrap();
+        assert_eq!(
+            spayd.alt_accounts(),
+            [Account::new("CZ6508000000192000145399")]
+        );
+        assert!(spayd.set(SpaydKey::AltAcc, "CZ65").is_err());
+
+        spayd.unset(SpaydKey::AltAcc).unwrap();
+        assert_eq!(spayd.alt_accounts(), []);
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use super::*;
+
+/// Amount formatted for the `AM` field, e.g. from minor units (cents)
+///
+/// Pass it to the builder's `amount` setter like a [`String`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Amount(String);
+
+impl Amount {
+    /// Amount from minor units (hundredths), e.g. `23950` becomes `239.50`
+    pub fn from_minor(minor: u64) -> Result<Self, SpaydError> {
+        Amount::checked(format!("{}.{:02}", minor / 100, minor % 100))
+    }
+
+    /// Amount from a floating point number, rounded half-up to two decimal places
+    ///
+    /// Rounding works on the shortest decimal representation of the number, so `2.675`
+    /// becomes `2.68` even though the nearest `f64` is slightly below it, and `0.1 + 0.2`
+    /// becomes `0.30`. NaN, infinities and negative numbers are rejected.
+    pub fn from_f64(value: f64) -> Result<Self, SpaydError> {
+        if !value.is_finite() {
+            return Err(SpaydError::InvalidAmount("Value is not a finite number"));
+        } else if value < 0.0 {
+            return Err(SpaydError::InvalidAmount("Value is negative"));
+        }
+
+        let decimal = value.abs().to_string();
+        let (int, frac) = decimal.split_once('.').unwrap_or((&decimal, ""));
+        let frac = format!("{:0<3}", frac);
+
+        let minor = int
+            .parse::<u64>()
+            .ok()
+            .and_then(|int| int.checked_mul(100))
+            .and_then(|int| int.checked_add(frac[..2].parse().ok()?))
+            .ok_or(SpaydError::AmountOutOfRange)?;
+
+        Amount::from_minor(if frac.as_bytes()[2] >= b'5' {
+            minor + 1
+        } else {
+            minor
+        })
+    }
+
+    /// Amount typed by a user, accepting a comma as the decimal separator (`239,50`)
+    ///
+    /// Inputs with both separators or with thousands grouping (`1,234.50`) are rejected.
+    /// The amount is normalized with [`normalize_amount`].
+    pub fn parse_lenient(amount: &str) -> Result<Self, SpaydError> {
+        let amount = amount.trim();
+
+        if amount.contains('.') && amount.contains(',') {
+            return Err(SpaydError::InvalidAmount(
+                "Value contains both `.` and `,` separators",
+            ));
+        }
+
+        normalize_amount(&amount.replacen(',', ".", 1)).map(Amount)
+    }
+
+    /// Amount as written in the `AM` field
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn checked(amount: String) -> Result<Self, SpaydError> {
+        check_amount(&amount, None)?;
+
+        Ok(Amount(amount))
+    }
+}
+
+/// Strip redundant leading zeros (`007.5` becomes `7.5`) and add a leading zero to bare
+/// fractions (`.50` becomes `0.50`)
+///
+/// The length limit is checked after normalization.
+pub fn normalize_amount(amount: &str) -> Result<String, SpaydError> {
+    let (int, frac) = match amount.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (amount, None),
+    };
+
+    let int = match int.trim_start_matches('0') {
+        "" if !int.is_empty() || frac.is_some() => "0",
+        int => int,
+    };
+
+    let amount = match frac {
+        Some(frac) => format!("{}.{}", int, frac),
+        None => int.to_string(),
+    };
+
+    Amount::checked(amount).map(String::from)
+}
+
+/// How the amount is written in the generated string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmountFormat {
+    /// As set (e.g. `5`)
+    #[default]
+    AsIs,
+
+    /// Padded to two decimal places (e.g. `5.00`)
+    TwoDecimals,
+}
+
+impl From<Amount> for String {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Spayd {
+    /// Amount in minor units (hundredths), if set and well-formed
+    pub fn amount_minor(&self) -> Option<u64> {
+        self.amount.as_deref().and_then(amount_minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn from_minor_works() {
+        assert_eq!(Amount::from_minor(23950).unwrap().as_str(), "239.50");
+        assert_eq!(Amount::from_minor(500).unwrap().as_str(), "5.00");
+        assert_eq!(Amount::from_minor(7).unwrap().as_str(), "0.07");
+        assert_eq!(
+            Amount::from_minor(999_999_999).unwrap().as_str(),
+            "9999999.99"
+        );
+        assert_eq!(
+            Amount::from_minor(1_000_000_000),
+            Err(SpaydError::AmountTooLong { len: 11, max: 10 })
+        );
+    }
+
+    #[test]
+    fn from_f64_works() {
+        let amount = |value: f64| Amount::from_f64(value).map(String::from);
+
+        assert_eq!(amount(239.5).as_deref(), Ok("239.50"));
+        assert_eq!(amount(0.1 + 0.2).as_deref(), Ok("0.30"));
+        assert_eq!(amount(2.675).as_deref(), Ok("2.68"));
+        assert_eq!(amount(1.005).as_deref(), Ok("1.01"));
+        assert_eq!(amount(2.674).as_deref(), Ok("2.67"));
+        assert_eq!(amount(0.0).as_deref(), Ok("0.00"));
+        assert_eq!(amount(-0.0).as_deref(), Ok("0.00"));
+        assert_eq!(amount(1e-7).as_deref(), Ok("0.00"));
+        assert_eq!(amount(9999999.994).as_deref(), Ok("9999999.99"));
+        assert_eq!(
+            amount(9999999.995),
+            Err(SpaydError::AmountTooLong { len: 11, max: 10 })
+        );
+        assert_eq!(amount(1e300), Err(SpaydError::AmountOutOfRange));
+    }
+
+    #[test]
+    fn from_f64_invalid_fails() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                Amount::from_f64(value),
+                Err(SpaydError::InvalidAmount("Value is not a finite number"))
+            );
+        }
+
+        assert_eq!(
+            Amount::from_f64(-1.0),
+            Err(SpaydError::InvalidAmount("Value is negative"))
+        );
+    }
+
+    #[test]
+    fn parse_lenient_works() {
+        assert_eq!(Amount::parse_lenient("239,50").unwrap().as_str(), "239.50");
+        assert_eq!(Amount::parse_lenient("1,2").unwrap().as_str(), "1.2");
+        assert_eq!(
+            Amount::parse_lenient(" 239.50 ").unwrap().as_str(),
+            "239.50"
+        );
+        assert_eq!(Amount::parse_lenient("5").unwrap().as_str(), "5");
+    }
+
+    #[test]
+    fn parse_lenient_invalid_fails() {
+        assert_eq!(
+            Amount::parse_lenient("1,234.50"),
+            Err(SpaydError::InvalidAmount(
+                "Value contains both `.` and `,` separators"
+            ))
+        );
+
+        for amount in ["1,234", "1,2,3", "1.2,3", "1 234,50", ",", ""] {
+            assert!(Amount::parse_lenient(amount).is_err(), "{}", amount);
+        }
+    }
+
+    #[test]
+    fn normalize_amount_works() {
+        assert_eq!(normalize_amount("007.5").as_deref(), Ok("7.5"));
+        assert_eq!(normalize_amount(".50").as_deref(), Ok("0.50"));
+        assert_eq!(normalize_amount("000").as_deref(), Ok("0"));
+        assert_eq!(normalize_amount("00.05").as_deref(), Ok("0.05"));
+        assert_eq!(normalize_amount("239.50").as_deref(), Ok("239.50"));
+        assert_eq!(normalize_amount("00000001234.50").as_deref(), Ok("1234.50"));
+        assert_eq!(Amount::parse_lenient(",5").unwrap().as_str(), "0.5");
+    }
+
+    #[test]
+    fn normalize_amount_invalid_fails() {
+        for amount in ["", ".", "5.", "1.234", "-5", "+5", "5 000"] {
+            assert!(normalize_amount(amount).is_err(), "{}", amount);
+        }
+
+        assert_eq!(
+            normalize_amount("0012345678.90"),
+            Err(SpaydError::AmountTooLong { len: 11, max: 10 })
+        );
+    }
+
+    #[test]
+    fn amount_minor_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount(Amount::from_minor(23950).unwrap())
+            .build();
+
+        assert_eq!(spayd.amount_minor(), Some(23950));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        assert_eq!(spayd.amount_minor(), None);
+    }
+}
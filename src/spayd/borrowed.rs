@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+
+use super::*;
+
+/// Borrowed view of a parsed SPAYD string
+///
+/// Values are slices of the parsed input; only values containing percent-escapes
+/// (or keys written in lowercase) are copied. Use [`SpaydRef::to_owned`] to
+/// convert it into a [`Spayd`].
+#[derive(Debug, Clone)]
+pub struct SpaydRef<'a> {
+    pub(super) account: Cow<'a, str>,
+    pub(super) bic: Option<Cow<'a, str>>,
+    pub(super) alt_accounts: Vec<Account>,
+    pub(super) amount: Option<Cow<'a, str>>,
+    pub(super) currency: Option<Cow<'a, str>>,
+    pub(super) reference: Option<Cow<'a, str>>,
+    pub(super) recipient: Option<Cow<'a, str>>,
+    pub(super) date: Option<Cow<'a, str>>,
+    pub(super) payment_type: Option<Cow<'a, str>>,
+    pub(super) message: Option<Cow<'a, str>>,
+    pub(super) notify: Option<NotifyType>,
+    pub(super) notify_address: Option<Cow<'a, str>>,
+    pub(super) extras: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    pub(super) version: SpaydVersion,
+    pub(super) validation: ValidationOptions,
+}
+
+impl<'a> SpaydRef<'a> {
+    /// Account number (`ACC`)
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// BIC written after the IBAN in `ACC`
+    pub fn bic(&self) -> Option<&str> {
+        self.bic.as_deref()
+    }
+
+    /// Alternative accounts (`ALT-ACC`)
+    pub fn alt_accounts(&self) -> &[Account] {
+        &self.alt_accounts
+    }
+
+    /// Amount (`AM`)
+    pub fn amount(&self) -> Option<&str> {
+        self.amount.as_deref()
+    }
+
+    /// Currency (`CC`)
+    pub fn currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+
+    /// Reference (`RF`)
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    /// Recipient name (`RN`)
+    pub fn recipient(&self) -> Option<&str> {
+        self.recipient.as_deref()
+    }
+
+    /// Due date (`DT`)
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    /// Payment type code (`PT`), e.g. `IP` for an instant payment
+    pub fn payment_type(&self) -> Option<&str> {
+        self.payment_type.as_deref()
+    }
+
+    /// Message (`MSG`)
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Notify type (`NT`)
+    pub fn notify(&self) -> Option<&NotifyType> {
+        self.notify.as_ref()
+    }
+
+    /// Notify address (`NTA`)
+    pub fn notify_address(&self) -> Option<&str> {
+        self.notify_address.as_deref()
+    }
+
+    /// Value of a field not modelled by this crate (e.g. `X-VS`)
+    pub fn extra(&self, key: &str) -> Option<&str> {
+        self.extras
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    /// Fields not modelled by this crate, in their original order
+    pub fn extras(&self) -> &[(Cow<'a, str>, Cow<'a, str>)] {
+        &self.extras
+    }
+
+    /// Format version
+    pub fn version(&self) -> SpaydVersion {
+        self.version
+    }
+
+    /// Convert into an owned [`Spayd`]
+    pub fn to_owned(&self) -> Spayd {
+        let owned = |value: &Option<Cow<'_, str>>| value.as_deref().map(str::to_string);
+
+        Spayd {
+            account: self.account.to_string(),
+            bic: owned(&self.bic),
+            alt_accounts: self.alt_accounts.clone(),
+            amount: owned(&self.amount),
+            currency: owned(&self.currency),
+            reference: owned(&self.reference),
+            recipient: owned(&self.recipient),
+            date: owned(&self.date),
+            payment_type: self.payment_type.as_deref().map(PaymentType::from_code),
+            message: owned(&self.message),
+            notification: self
+                .notify
+                .clone()
+                .zip(owned(&self.notify_address))
+                .map(|(notify, address)| Notification::new(notify, address)),
+            pending_notify: None,
+            extras: self
+                .extras
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            version: self.version,
+            validation: self.validation.clone(),
+            amount_format: AmountFormat::default(),
+            field_order: FieldOrder::default(),
+            auto_uppercase: false,
+            lossy: false,
+        }
+    }
+
+    pub(super) fn validate(&self) -> Result<(), SpaydError> {
+        self.fields().validate()?;
+
+        match self.payload_errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Errors of the checks run after the field checks, see [`Spayd::validate`]
+    pub(super) fn payload_errors(&self) -> Vec<SpaydError> {
+        self.to_owned().payload_errors()
+    }
+
+    pub(super) fn fields(&self) -> Fields<'_> {
+        Fields {
+            account: &self.account,
+            bic: self.bic.as_deref(),
+            alt_accounts: &self.alt_accounts,
+            amount: self.amount.as_deref(),
+            currency: self.currency.as_deref(),
+            reference: self.reference.as_deref(),
+            recipient: self.recipient.as_deref(),
+            date: self.date.as_deref(),
+            payment_type: self.payment_type.as_deref(),
+            message: self.message.as_deref(),
+            notify: self.notify.clone(),
+            notify_address: self.notify_address.as_deref(),
+            options: &self.validation,
+            amount_format: AmountFormat::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+    use std::borrow::Cow;
+
+    #[test]
+    fn parse_borrows_input() {
+        let input =
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA%20ZA%20ZBOZI*X-VS:12";
+        let spayd = SpaydRef::parse(input).unwrap();
+
+        assert!(matches!(
+            spayd.account,
+            Cow::Borrowed("CZ5508000000001234567899")
+        ));
+        assert!(matches!(spayd.amount, Some(Cow::Borrowed("239.50"))));
+        assert!(matches!(spayd.message, Some(Cow::Owned(_))));
+        assert_eq!(spayd.message(), Some("PLATBA ZA ZBOZI"));
+        assert_eq!(spayd.extra("X-VS"), Some("12"));
+    }
+
+    #[test]
+    fn to_owned_works() {
+        let input = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*PT:IP*NT:P*NTA:+420123456789";
+        let spayd = SpaydRef::parse(input).unwrap().to_owned();
+
+        assert!(matches!(spayd.payment_type, Some(PaymentType::Instant)));
+        assert_eq!(spayd.spayd_string().unwrap(), input);
+    }
+
+    #[test]
+    fn parse_validates_values() {
+        let result = SpaydRef::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.500");
+
+        assert!(matches!(
+            result,
+            Err(SpaydParseError::InvalidValue {
+                key: SpaydKey::Am,
+                offset: 40,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+use super::*;
+
+/// Lookup table of the CRC-32 (IEEE 802.3) polynomial, reflected
+const TABLE: [u32; 256] = table();
+
+const fn table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// CRC-32 as used by zlib and the SPAYD `CRC32` field
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &b| {
+        (crc >> 8) ^ TABLE[((crc ^ u32::from(b)) & 0xFF) as usize]
+    })
+}
+
+/// Fields in [`FieldOrder::SpecCanonical`] order after the header and version, the form the
+/// checksum is computed over
+pub(crate) fn canonical_payload(
+    version: SpaydVersion,
+    mut pairs: Vec<(SpaydKey, Cow<'_, str>)>,
+) -> String {
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut v: Vec<String> = Vec::with_capacity(pairs.len() + 2);
+
+    v.push("SPD".to_string()); // header
+    v.push(version.to_string()); // version
+
+    for (key, value) in pairs {
+        v.push(format!("{}:{}", key, value));
+    }
+
+    v.join("*")
+}
+
+/// Checksum of the canonical payload, as written in the `CRC32` field
+pub(crate) fn checksum(version: SpaydVersion, pairs: Vec<(SpaydKey, Cow<'_, str>)>) -> String {
+    format!(
+        "{:08X}",
+        crc32(canonical_payload(version, pairs).as_bytes())
+    )
+}
+
+impl Spayd {
+    /// Generate the normalized SPAYD string (see [`Spayd::canonical_string`]) followed by its
+    /// CRC-32 checksum, e.g. `...*X-VS:1234567890*CRC32:FF2552AF`
+    ///
+    /// Reading apps sort the fields the same way to verify the checksum. A `CRC32` field set
+    /// as an extra is replaced.
+    pub fn spayd_string_with_crc(&self) -> Result<String, SpaydError> {
+        let mut spayd = Cow::Borrowed(self);
+
+        if self.extra("CRC32").is_some() {
+            spayd.to_mut().extras.retain(|(key, _)| key != "CRC32");
+        }
+
+        let canonical = spayd.canonical_string()?;
+        let crc = crc32(canonical.as_bytes());
+
+        Ok(format!("{}*CRC32:{:08X}", canonical, crc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::crc::crc32;
+    use crate::spayd::*;
+
+    #[test]
+    fn crc32_works() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn spayd_string_with_crc_works() {
+        // checksum of this crate's canonical form; a vector from an independent SPAYD
+        // generator is still missing
+        let spayd = Spayd::builder()
+            .account("CZ5855000000001265098001".to_string())
+            .bic("RZBCCZPP")
+            .amount("480.50")
+            .currency("CZK")
+            .message("PLATBA ZA ELEKTRINU")
+            .extras(vec![
+                ("X-VS".to_string(), "1234567890".to_string()),
+                ("X-KS".to_string(), "0308".to_string()),
+            ])
+            .build();
+        let expected = "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:480.50*CC:CZK\
+                        *MSG:PLATBA ZA ELEKTRINU*X-KS:0308*X-VS:1234567890*CRC32:FF2552AF";
+
+        assert_eq!(spayd.spayd_string_with_crc().unwrap(), expected);
+
+        let parsed = Spayd::parse(expected).unwrap();
+
+        assert_eq!(parsed.extra("CRC32"), None);
+        assert_eq!(parsed.spayd_string_with_crc().unwrap(), expected);
+    }
+
+    #[test]
+    fn edited_payload_parses_again() {
+        let input = "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:480.50*CC:CZK\
+                     *MSG:PLATBA ZA ELEKTRINU*X-KS:0308*X-VS:1234567890*CRC32:FF2552AF";
+        let mut spayd = Spayd::parse(input).unwrap();
+
+        spayd.set(SpaydKey::Am, "490.50").unwrap();
+
+        let output = spayd.spayd_string().unwrap();
+
+        assert!(!output.contains("CRC32"));
+        assert!(Spayd::parse(&output).unwrap().semantically_eq(&spayd));
+
+        let output = spayd.spayd_string_with_crc().unwrap();
+
+        assert!(output.ends_with("*CRC32:493EB2DF"));
+        assert!(Spayd::parse(&output).unwrap().semantically_eq(&spayd));
+    }
+
+    #[test]
+    fn parse_verifies_crc() {
+        // fields in another order than the checksum is computed over
+        let input = "SPD*1.0*AM:480.50*ACC:CZ5855000000001265098001+RZBCCZPP*X-VS:1234567890\
+                     *CC:CZK*MSG:PLATBA ZA ELEKTRINU*CRC32:ff2552af*X-KS:0308";
+
+        assert!(Spayd::parse(input).is_ok());
+        assert!(Spayd::parse("SPD*1.0*ACC:CZ5855000000001265098001*AM:480.50").is_ok());
+
+        let input = "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:490.50*CC:CZK\
+                     *MSG:PLATBA ZA ELEKTRINU*X-KS:0308*X-VS:1234567890*CRC32:FF2552AF";
+        let offset = input.find("CRC32:").unwrap() + 6;
+        let result = Spayd::parse(input);
+
+        assert_eq!(
+            result.as_ref().unwrap_err(),
+            &SpaydParseError::CrcMismatch {
+                expected: "FF2552AF".to_string(),
+                computed: "493EB2DF".to_string(),
+                offset
+            }
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            format!(
+                "checksum FF2552AF at offset {} does not match the payload, computed 493EB2DF",
+                offset
+            )
+        );
+
+        let options = ParseOptions {
+            crc_mismatch: CrcMismatchPolicy::Warn,
+            ..Default::default()
+        };
+
+        assert!(Spayd::parse_with(input, &options).is_ok());
+
+        let (spayd, warnings) = Spayd::parse_lenient_with(input, &options).unwrap();
+
+        assert_eq!(spayd.get(SpaydKey::Am).as_deref(), Some("490.50"));
+        assert_eq!(
+            warnings,
+            [SpaydWarning {
+                key: SpaydKey::Custom("CRC32".to_string()),
+                offset,
+                reason: SpaydError::CrcMismatch {
+                    expected: "FF2552AF".to_string(),
+                    computed: "493EB2DF".to_string()
+                }
+            }]
+        );
+    }
+
+    #[test]
+    fn spayd_string_with_crc_invalid_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5855000000001265098001".to_string())
+            .amount("1.234")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string_with_crc(),
+            Err(SpaydError::AmountTooManyDecimals { found: 3 })
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use super::*;
+
+/// Combination of fields that are valid on their own, but not together
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// Recurring payment (`X-PER`) without a due date (`DT`) to start from
+    RecurrenceWithoutDate,
+
+    /// Account outside the Czech Republic without a currency (`CC`), which then defaults to `CZK`
+    ForeignAccountWithoutCurrency,
+
+    /// Instant payment (`PT:IP`) due in the future, which can't be scheduled
+    ScheduledInstantPayment,
+}
+
+impl Inconsistency {
+    /// Key of the field to change
+    pub fn key(&self) -> SpaydKey {
+        match self {
+            Inconsistency::RecurrenceWithoutDate => SpaydKey::Dt,
+            Inconsistency::ForeignAccountWithoutCurrency => SpaydKey::Cc,
+            Inconsistency::ScheduledInstantPayment => SpaydKey::Pt,
+        }
+    }
+
+    /// Human readable description of the inconsistency
+    pub fn message(&self) -> &'static str {
+        match self {
+            Inconsistency::RecurrenceWithoutDate => "Recurring payment (X-PER) needs a due date",
+            Inconsistency::ForeignAccountWithoutCurrency => {
+                "Currency is required for an account outside CZ"
+            }
+            Inconsistency::ScheduledInstantPayment => "Instant payment can't be due in the future",
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Checks of field combinations, done with [`ValidationLevel::Strict`]
+///
+/// All are enabled by default; banks differ on some of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrossFieldRules {
+    /// See [`Inconsistency::RecurrenceWithoutDate`]
+    pub recurrence_without_date: bool,
+
+    /// See [`Inconsistency::ForeignAccountWithoutCurrency`]
+    pub foreign_account_without_currency: bool,
+
+    /// See [`Inconsistency::ScheduledInstantPayment`]
+    pub scheduled_instant_payment: bool,
+}
+
+impl Default for CrossFieldRules {
+    fn default() -> Self {
+        CrossFieldRules {
+            recurrence_without_date: true,
+            foreign_account_without_currency: true,
+            scheduled_instant_payment: true,
+        }
+    }
+}
+
+impl Spayd {
+    /// Inconsistent field combinations, if validating with [`ValidationLevel::Strict`]
+    pub(crate) fn inconsistencies(&self) -> Vec<SpaydError> {
+        let options = &self.validation;
+        let rules = options.cross_field_rules;
+        let mut found = Vec::new();
+
+        if !options.strict() {
+            return Vec::new();
+        }
+
+        let recurring = self
+            .extras
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("X-PER"));
+
+        if rules.recurrence_without_date && recurring && self.date.is_none() {
+            found.push(Inconsistency::RecurrenceWithoutDate);
+        }
+
+        let default_currency = options
+            .profile
+            .as_ref()
+            .and_then(|profile| profile.default_currency);
+
+        if rules.foreign_account_without_currency
+            && self.currency.is_none()
+            && default_currency.is_none()
+            && !self.account.is_empty()
+            && !self.account.starts_with("CZ")
+        {
+            found.push(Inconsistency::ForeignAccountWithoutCurrency);
+        }
+
+        let date = self
+            .date
+            .as_deref()
+            .and_then(|date| date.parse::<SpaydDate>().ok());
+
+        if let (true, Some(PaymentType::Instant), Some(date)) =
+            (rules.scheduled_instant_payment, &self.payment_type, date)
+        {
+            let today = options.today.unwrap_or_else(SpaydDate::today);
+
+            if date > today {
+                found.push(Inconsistency::ScheduledInstantPayment);
+            }
+        }
+
+        found.into_iter().map(SpaydError::Inconsistent).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    fn strict(rules: CrossFieldRules) -> ValidationOptions {
+        ValidationOptions {
+            level: ValidationLevel::Strict,
+            cross_field_rules: rules,
+            today: Some(SpaydDate::new(2024, 6, 1).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inconsistent_fields_fail() {
+        let recurring = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .extras(vec![("X-PER".to_string(), "30".to_string())])
+            .validation(strict(Default::default()))
+            .build();
+        let foreign = Spayd::builder()
+            .account("DE89370400440532013000".to_string())
+            .validation(strict(Default::default()))
+            .build();
+        let instant = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .payment_type(PaymentType::Instant)
+            .date("20240602")
+            .validation(strict(Default::default()))
+            .build();
+
+        for (spayd, inconsistency) in [
+            (&recurring, Inconsistency::RecurrenceWithoutDate),
+            (&foreign, Inconsistency::ForeignAccountWithoutCurrency),
+            (&instant, Inconsistency::ScheduledInstantPayment),
+        ] {
+            assert_eq!(
+                spayd.validate(),
+                Err(SpaydError::Inconsistent(inconsistency))
+            );
+            assert!(spayd.spayd_string_with(&Default::default()).is_ok());
+        }
+
+        assert_eq!(
+            foreign.validate().unwrap_err().to_string(),
+            "invalid CC: Currency is required for an account outside CZ"
+        );
+    }
+
+    #[test]
+    fn cross_field_rules_can_be_disabled() {
+        let spayd = Spayd::builder()
+            .account("DE89370400440532013000".to_string())
+            .payment_type(PaymentType::Instant)
+            .date("20240602")
+            .validation(strict(CrossFieldRules {
+                foreign_account_without_currency: false,
+                ..Default::default()
+            }))
+            .build();
+
+        assert_eq!(
+            spayd.validate_all(),
+            Err(vec![SpaydError::Inconsistent(
+                Inconsistency::ScheduledInstantPayment
+            )])
+        );
+
+        let spayd = Spayd::builder()
+            .account("DE89370400440532013000".to_string())
+            .currency("EUR")
+            .payment_type(PaymentType::Instant)
+            .date("20240601")
+            .validation(strict(Default::default()))
+            .build();
+
+        assert_eq!(spayd.validate(), Ok(()));
+    }
+}
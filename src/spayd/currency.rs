@@ -0,0 +1,295 @@
+use std::borrow::Cow;
+
+use super::*;
+
+/// Value accepted by the builder's `currency` setter: a [`String`], `&str` or [`CurrencyCode`]
+pub trait IntoCurrency {
+    /// Alphabetic currency code (e.g. `CZK`)
+    fn into_currency(self) -> String;
+}
+
+impl IntoCurrency for String {
+    fn into_currency(self) -> String {
+        self
+    }
+}
+
+impl IntoCurrency for &str {
+    fn into_currency(self) -> String {
+        self.to_string()
+    }
+}
+
+impl IntoCurrency for CurrencyCode {
+    fn into_currency(self) -> String {
+        self.alpha().to_string()
+    }
+}
+
+/// Uppercase a currency code (`czk` becomes `CZK`)
+pub(crate) fn normalize(currency: Cow<'_, str>) -> Cow<'_, str> {
+    if !currency.chars().any(|c| c.is_ascii_lowercase()) {
+        return currency;
+    }
+
+    Cow::Owned(currency.to_ascii_uppercase())
+}
+
+/// Codes that are no national currency: funds codes, precious metals, bond market and other
+/// accounting units, and the testing and no-currency codes
+const NON_NATIONAL: [CurrencyCode; 22] = [
+    CurrencyCode::BOV,
+    CurrencyCode::CHE,
+    CurrencyCode::CHW,
+    CurrencyCode::CLF,
+    CurrencyCode::COU,
+    CurrencyCode::MXV,
+    CurrencyCode::USN,
+    CurrencyCode::UYI,
+    CurrencyCode::UYW,
+    CurrencyCode::XAG,
+    CurrencyCode::XAU,
+    CurrencyCode::XBA,
+    CurrencyCode::XBB,
+    CurrencyCode::XBC,
+    CurrencyCode::XBD,
+    CurrencyCode::XDR,
+    CurrencyCode::XPD,
+    CurrencyCode::XPT,
+    CurrencyCode::XSU,
+    CurrencyCode::XTS,
+    CurrencyCode::XUA,
+    CurrencyCode::XXX,
+];
+
+/// Currencies replaced since the `iso_4217` code list was published
+const RETIRED: [CurrencyCode; 4] = [
+    CurrencyCode::CUC,
+    CurrencyCode::HRK,
+    CurrencyCode::SLL,
+    CurrencyCode::ZWL,
+];
+
+/// Check that a currency is an active national currency, see
+/// [`ValidationOptions::reject_unusual_currencies`]
+pub(crate) fn check_usual(
+    currency: CurrencyCode,
+    options: &ValidationOptions,
+) -> Result<(), SpaydError> {
+    let unusual = options.rejected_currencies.contains(&currency)
+        || (NON_NATIONAL.contains(&currency) || RETIRED.contains(&currency))
+            && !options.accepted_unusual_currencies.contains(&currency);
+
+    if unusual {
+        return Err(SpaydError::CurrencyNotPermitted { currency });
+    }
+
+    Ok(())
+}
+
+impl Spayd {
+    /// Currency (`CC`) as a typed code, if set and valid
+    pub fn currency_code(&self) -> Option<CurrencyCode> {
+        CurrencyCode::try_from(self.currency.as_deref()?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn currency_code_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .currency(CurrencyCode::EUR)
+            .build();
+
+        assert_eq!(spayd.currency_code(), Some(CurrencyCode::EUR));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*CC:EUR"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .currency("CZK")
+            .build();
+
+        assert_eq!(spayd.currency_code(), Some(CurrencyCode::CZK));
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        assert_eq!(spayd.currency_code(), None);
+    }
+
+    #[test]
+    fn currency_is_normalized() {
+        for (currency, expected) in [("czk", "CZK"), ("Czk", "CZK"), ("eUr", "EUR")] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .currency(currency)
+                .build();
+
+            assert_eq!(
+                spayd.spayd_string().unwrap(),
+                format!("SPD*1.0*ACC:CZ5508000000001234567899*CC:{}", expected)
+            );
+
+            let input = format!("SPD*1.0*ACC:CZ5508000000001234567899*CC:{}", currency);
+
+            assert_eq!(
+                Spayd::parse(&input).unwrap().currency.as_deref(),
+                Some(expected)
+            );
+        }
+
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        spayd.set(SpaydKey::Cc, "eur").unwrap();
+        assert_eq!(spayd.currency_code(), Some(CurrencyCode::EUR));
+    }
+
+    #[test]
+    fn unusual_currencies_fail() {
+        let spayd = |currency: &str, options: ValidationOptions| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .currency(currency)
+                .validation(options)
+                .build()
+        };
+        let options = ValidationOptions {
+            reject_unusual_currencies: true,
+            ..Default::default()
+        };
+
+        assert!(spayd("XAU", Default::default()).spayd_string().is_ok());
+        assert!(spayd("EUR", options.clone()).spayd_string().is_ok());
+        assert!(spayd("XOF", options.clone()).spayd_string().is_ok());
+
+        for currency in ["XAU", "XDR", "BOV", "HRK"] {
+            assert_eq!(
+                spayd(currency, options.clone()).spayd_string(),
+                Err(SpaydError::CurrencyNotPermitted {
+                    currency: CurrencyCode::try_from(currency).unwrap()
+                }),
+                "{}",
+                currency
+            );
+        }
+
+        assert_eq!(
+            spayd("XAU", options.clone())
+                .spayd_string()
+                .unwrap_err()
+                .to_string(),
+            "invalid CC: Currency code XAU (Gold (one troy ounce)) is recognized, but not \
+             permitted on a payment"
+        );
+        assert_eq!(
+            spayd("CSK", options.clone()).spayd_string(),
+            Err(SpaydError::InvalidCurrency("Invalid currency code"))
+        );
+
+        let strict = ValidationOptions {
+            level: ValidationLevel::Strict,
+            ..Default::default()
+        };
+
+        assert!(spayd("XTS", strict).spayd_string().is_err());
+    }
+
+    #[test]
+    fn unusual_currency_overrides_work() {
+        let options = ValidationOptions {
+            reject_unusual_currencies: true,
+            accepted_unusual_currencies: vec![CurrencyCode::XAU],
+            rejected_currencies: vec![CurrencyCode::USD],
+            ..Default::default()
+        };
+        let spayd = |currency: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .currency(currency)
+                .validation(options.clone())
+                .build()
+        };
+
+        assert!(spayd("XAU").spayd_string().is_ok());
+        assert!(spayd("XAG").spayd_string().is_err());
+        assert_eq!(
+            spayd("USD").spayd_string(),
+            Err(SpaydError::CurrencyNotPermitted {
+                currency: CurrencyCode::USD
+            })
+        );
+    }
+
+    #[test]
+    fn allowed_currencies_works() {
+        let options = ValidationOptions {
+            allowed_currencies: Some(vec![CurrencyCode::CZK, CurrencyCode::EUR]),
+            ..Default::default()
+        };
+        let spayd = |currency: Option<&str>, options: &ValidationOptions| {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .validation(options.clone());
+
+            match currency {
+                Some(currency) => spayd.currency(currency).build(),
+                None => spayd.build(),
+            }
+        };
+
+        assert!(spayd(Some("EUR"), &options).spayd_string().is_ok());
+        assert!(spayd(None, &options).spayd_string().is_ok());
+
+        let result = spayd(Some("USD"), &options).spayd_string();
+
+        assert_eq!(
+            result,
+            Err(SpaydError::CurrencyNotAllowed {
+                currency: CurrencyCode::USD,
+                allowed: vec![CurrencyCode::CZK, CurrencyCode::EUR],
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid CC: Currency USD not allowed, expected one of CZK, EUR"
+        );
+
+        let options = ValidationOptions {
+            allowed_currencies: Some(vec![CurrencyCode::EUR]),
+            ..Default::default()
+        };
+
+        assert!(spayd(None, &options).spayd_string().is_err());
+
+        let options = ValidationOptions {
+            default_currency: None,
+            ..options
+        };
+
+        assert!(spayd(None, &options).spayd_string().is_ok());
+    }
+
+    #[test]
+    fn invalid_currency_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .currency("cze")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidCurrency("Invalid currency code"))
+        );
+    }
+}
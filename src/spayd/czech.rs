@@ -0,0 +1,316 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+/// Czech domestic account number (`prefix-number/bank code`, e.g. `19-2000145399/0800`)
+///
+/// Czech invoices state accounts in this format; use [`CzechAccount::to_iban`] to get
+/// the IBAN for [`Spayd`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CzechAccount {
+    prefix: Option<String>,
+    number: String,
+    bank_code: String,
+}
+
+impl CzechAccount {
+    /// Create an account from its parts
+    ///
+    /// The prefix has 1 to 6 digits, the number 2 to 10 digits and the bank code
+    /// exactly 4 digits.
+    pub fn new(prefix: Option<&str>, number: &str, bank_code: &str) -> Result<Self, SpaydError> {
+        CzechAccount::new_with(prefix, number, bank_code, &ValidationOptions::default())
+    }
+
+    /// Like [`CzechAccount::new`], also checking the prefix and number with the modulo-11 rule
+    /// if [`ValidationOptions::check_czech_account`] is set or the level is strict
+    pub fn new_with(
+        prefix: Option<&str>,
+        number: &str,
+        bank_code: &str,
+        options: &ValidationOptions,
+    ) -> Result<Self, SpaydError> {
+        let digits = |value: &str| value.bytes().all(|b| b.is_ascii_digit());
+
+        if let Some(prefix) = prefix {
+            if prefix.is_empty() || prefix.len() > 6 || !digits(prefix) {
+                return Err(SpaydError::InvalidAccountNumber(
+                    "Account prefix must have 1 to 6 digits",
+                ));
+            }
+        }
+
+        if number.len() < 2 || number.len() > 10 || !digits(number) {
+            return Err(SpaydError::InvalidAccountNumber(
+                "Account number must have 2 to 10 digits",
+            ));
+        }
+
+        if (options.check_czech_account || options.strict())
+            && (prefix.is_some_and(|prefix| !mod11_valid(prefix)) || !mod11_valid(number))
+        {
+            return Err(SpaydError::InvalidAccountNumber(
+                "Czech account number fails modulo-11 check",
+            ));
+        }
+
+        if bank_code.len() != 4 || !digits(bank_code) {
+            return Err(SpaydError::InvalidAccountNumber(
+                "Bank code must have 4 digits",
+            ));
+        }
+
+        Ok(CzechAccount {
+            prefix: prefix.map(str::to_string),
+            number: number.to_string(),
+            bank_code: bank_code.to_string(),
+        })
+    }
+
+    /// Extract the domestic account from a `CZ` IBAN in the electronic format
+    ///
+    /// Leading zeros of the prefix and number are stripped; a zero prefix is omitted.
+    /// Returns `None` for IBANs of other countries or with a malformed BBAN.
+    pub fn from_iban(iban: &str) -> Option<Self> {
+        let bban = iban.strip_prefix("CZ")?.get(2..)?;
+
+        if bban.len() != 20 || !bban.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let (bank_code, account) = bban.split_at(4);
+        let (prefix, number) = account.split_at(6);
+        let prefix = prefix.trim_start_matches('0');
+        let number = number.trim_start_matches('0');
+
+        Some(CzechAccount {
+            prefix: (!prefix.is_empty()).then(|| prefix.to_string()),
+            number: if number.is_empty() { "0" } else { number }.to_string(),
+            bank_code: bank_code.to_string(),
+        })
+    }
+
+    /// Account prefix, if any
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Account number
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    /// Bank code
+    pub fn bank_code(&self) -> &str {
+        &self.bank_code
+    }
+
+    /// Convert to IBAN in the electronic format (e.g. `CZ6508000000192000145399`)
+    pub fn to_iban(&self) -> String {
+        let bban = format!(
+            "{}{:0>6}{:0>10}",
+            self.bank_code,
+            self.prefix.as_deref().unwrap_or(""),
+            self.number
+        );
+        let check_digits = iban::check_digits("CZ", &bban).expect("BBAN has only digits");
+
+        format!("CZ{}{}", check_digits, bban)
+    }
+}
+
+impl FromStr for CzechAccount {
+    type Err = SpaydError;
+
+    /// Parse `prefix-number/bank code`; the prefix is optional
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (account, bank_code) =
+            s.trim()
+                .split_once('/')
+                .ok_or(SpaydError::InvalidAccountNumber(
+                    "Account number is missing the bank code",
+                ))?;
+
+        match account.split_once('-') {
+            Some((prefix, number)) => CzechAccount::new(Some(prefix), number, bank_code),
+            None => CzechAccount::new(None, account, bank_code),
+        }
+    }
+}
+
+impl fmt::Display for CzechAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref prefix) = self.prefix {
+            write!(f, "{}-", prefix)?;
+        }
+
+        write!(f, "{}/{}", self.number, self.bank_code)
+    }
+}
+
+/// Verify the weighted modulo-11 check of a Czech account prefix or number
+///
+/// Digits are weighted from the right by 1, 2, 4, 8, 5, 10, 9, 7, 3, 6 and the sum must be
+/// divisible by 11.
+pub(crate) fn mod11_valid(digits: &str) -> bool {
+    const WEIGHTS: [u32; 10] = [1, 2, 4, 8, 5, 10, 9, 7, 3, 6];
+
+    digits.len() <= WEIGHTS.len()
+        && digits
+            .chars()
+            .rev()
+            .zip(WEIGHTS)
+            .try_fold(0, |sum, (c, weight)| Some(sum + c.to_digit(10)? * weight))
+            .is_some_and(|sum| sum % 11 == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn to_iban_works() {
+        let account: CzechAccount = "19-2000145399/0800".parse().unwrap();
+
+        assert_eq!(account.to_iban(), "CZ6508000000192000145399");
+
+        let account = CzechAccount::new(None, "1234567899", "0800").unwrap();
+
+        assert_eq!(account.to_iban(), "CZ5508000000001234567899");
+
+        let spayd = Spayd::builder()
+            .account(account.to_iban())
+            .amount("239.50".to_string())
+            .build();
+
+        assert!(spayd.spayd_string().is_ok());
+    }
+
+    #[test]
+    fn from_iban_works() {
+        assert_eq!(
+            CzechAccount::from_iban("CZ6508000000192000145399")
+                .unwrap()
+                .to_string(),
+            "19-2000145399/0800"
+        );
+        assert_eq!(
+            CzechAccount::from_iban("CZ5508000000001234567899")
+                .unwrap()
+                .to_string(),
+            "1234567899/0800"
+        );
+        assert_eq!(
+            CzechAccount::from_iban("CZ5301000000000000012345")
+                .unwrap()
+                .to_string(),
+            "12345/0100"
+        );
+        assert_eq!(CzechAccount::from_iban("SK3112000000198742637541"), None);
+        assert_eq!(CzechAccount::from_iban("CZ65080000001920001453"), None);
+    }
+
+    #[test]
+    fn account_czech_format_works() {
+        let spayd = Spayd::builder()
+            .account("CZ6508000000192000145399".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(
+            spayd.account_czech_format().as_deref(),
+            Some("19-2000145399/0800")
+        );
+
+        let spayd = Spayd::builder()
+            .account("DE89370400440532013000".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        assert_eq!(spayd.account_czech_format(), None);
+    }
+
+    #[test]
+    fn mod11_valid_works() {
+        assert!(czech::mod11_valid("19"));
+        assert!(czech::mod11_valid("2000145399"));
+        assert!(czech::mod11_valid("1265098001"));
+        assert!(!czech::mod11_valid("1234567890"));
+        assert!(!czech::mod11_valid("2000145398"));
+        assert!(!czech::mod11_valid("12345678901"));
+    }
+
+    #[test]
+    fn display_works() {
+        let account: CzechAccount = "19-2000145399/0800".parse().unwrap();
+
+        assert_eq!(account.to_string(), "19-2000145399/0800");
+        assert_eq!(
+            CzechAccount::new(None, "1234567899", "0800")
+                .unwrap()
+                .to_string(),
+            "1234567899/0800"
+        );
+    }
+
+    #[test]
+    fn invalid_account_fails() {
+        assert_eq!(
+            CzechAccount::new(None, "1234567899", "800"),
+            Err(SpaydError::InvalidAccountNumber(
+                "Bank code must have 4 digits"
+            ))
+        );
+        assert_eq!(
+            CzechAccount::new(None, "1234567899", "08O0"),
+            Err(SpaydError::InvalidAccountNumber(
+                "Bank code must have 4 digits"
+            ))
+        );
+        assert_eq!(
+            CzechAccount::new(None, "12345678901", "0800"),
+            Err(SpaydError::InvalidAccountNumber(
+                "Account number must have 2 to 10 digits"
+            ))
+        );
+        assert!(CzechAccount::new(None, "1234567890", "0800").is_ok());
+
+        let options = ValidationOptions {
+            check_czech_account: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CzechAccount::new_with(None, "1234567890", "0800", &options),
+            Err(SpaydError::InvalidAccountNumber(
+                "Czech account number fails modulo-11 check"
+            ))
+        );
+
+        let options = ValidationOptions {
+            level: ValidationLevel::Strict,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CzechAccount::new_with(Some("18"), "2000145399", "0800", &options),
+            Err(SpaydError::InvalidAccountNumber(
+                "Czech account number fails modulo-11 check"
+            ))
+        );
+        assert!(CzechAccount::new_with(Some("19"), "2000145399", "0800", &options).is_ok());
+        assert_eq!(
+            CzechAccount::new(Some("1234567"), "1234567899", "0800"),
+            Err(SpaydError::InvalidAccountNumber(
+                "Account prefix must have 1 to 6 digits"
+            ))
+        );
+        assert_eq!(
+            "1234567899".parse::<CzechAccount>(),
+            Err(SpaydError::InvalidAccountNumber(
+                "Account number is missing the bank code"
+            ))
+        );
+    }
+}
@@ -0,0 +1,455 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::*;
+
+/// Due date for the `DT` field, checked against the calendar
+///
+/// Pass it to the builder's `date` setter like a [`String`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpaydDate {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl SpaydDate {
+    /// Date from a year (1000-2999), month (1-12) and day
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, SpaydError> {
+        if !(1000..=2999).contains(&year) {
+            return Err(SpaydError::InvalidDate("Year is out of range 1000-2999"));
+        } else if !is_valid_date(year, month, day) {
+            return Err(SpaydError::InvalidDate("Date does not exist"));
+        }
+
+        Ok(SpaydDate { year, month, day })
+    }
+
+    /// Year
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Month (1-12)
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Day of the month
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Current date in UTC, from the system clock
+    ///
+    /// The local time zone is not available without a date library; near midnight, the UTC
+    /// date may differ from the local one.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+
+        // days since 1970-01-01 to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+        let days = secs / 86_400 + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+        SpaydDate {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+
+    /// Date `days` days from today (in UTC), e.g. for invoices due in 14 days
+    pub fn due_in_days(days: u16) -> Result<Self, SpaydError> {
+        SpaydDate::today().add_days(days)
+    }
+
+    /// Date `days` days after this one
+    pub fn add_days(self, days: u16) -> Result<Self, SpaydError> {
+        let (year, month, day) = self.shifted(days);
+
+        SpaydDate::new(year, month, day)
+    }
+
+    /// Year, month and day `days` days after this date, past 2999 too
+    fn shifted(self, days: u16) -> (u16, u8, u8) {
+        let SpaydDate {
+            mut year,
+            mut month,
+            day,
+        } = self;
+        let mut day = u32::from(day) + u32::from(days);
+
+        while day > u32::from(days_in_month(year, month)) {
+            day -= u32::from(days_in_month(year, month));
+            month += 1;
+
+            if month > 12 {
+                month = 1;
+                year += 1;
+            }
+        }
+
+        (year, month, day as u8)
+    }
+}
+
+// The builder's type parameter holds the state of each field, in declaration order; `date` must
+// be unset here.
+#[allow(clippy::type_complexity)]
+impl<Acc, Bic, Alt, Am, Cc, Rf, Rn, Pt, Msg, N, Ext, Ver, Val, Fmt, Order, Up, Lossy>
+    SpaydBuilder<(
+        Acc,
+        Bic,
+        Alt,
+        Am,
+        Cc,
+        Rf,
+        Rn,
+        (),
+        Pt,
+        Msg,
+        N,
+        Ext,
+        Ver,
+        Val,
+        Fmt,
+        Order,
+        Up,
+        Lossy,
+    )>
+{
+    /// Set the due date (`DT`) to `days` days from today, in UTC (see [`SpaydDate::today`])
+    pub fn due_in_days(
+        self,
+        days: u16,
+    ) -> SpaydBuilder<(
+        Acc,
+        Bic,
+        Alt,
+        Am,
+        Cc,
+        Rf,
+        Rn,
+        (Option<String>,),
+        Pt,
+        Msg,
+        N,
+        Ext,
+        Ver,
+        Val,
+        Fmt,
+        Order,
+        Up,
+        Lossy,
+    )> {
+        self.due_in_days_from(SpaydDate::today(), days)
+    }
+
+    /// Set the due date (`DT`) to `days` days after `today`
+    ///
+    /// A date after 2999 is set as well and fails validation.
+    pub fn due_in_days_from(
+        self,
+        today: SpaydDate,
+        days: u16,
+    ) -> SpaydBuilder<(
+        Acc,
+        Bic,
+        Alt,
+        Am,
+        Cc,
+        Rf,
+        Rn,
+        (Option<String>,),
+        Pt,
+        Msg,
+        N,
+        Ext,
+        Ver,
+        Val,
+        Fmt,
+        Order,
+        Up,
+        Lossy,
+    )> {
+        let (year, month, day) = today.shifted(days);
+
+        self.date(format!("{:04}{:02}{:02}", year, month, day))
+    }
+}
+
+impl FromStr for SpaydDate {
+    type Err = SpaydError;
+
+    /// Parse a date in the `YYYYMMDD` format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_digit()) || !s.starts_with(['1', '2']) {
+            return Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format"));
+        }
+
+        SpaydDate::new(
+            s[..4].parse().unwrap(),
+            s[4..6].parse().unwrap(),
+            s[6..].parse().unwrap(),
+        )
+    }
+}
+
+impl fmt::Display for SpaydDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl From<SpaydDate> for String {
+    fn from(date: SpaydDate) -> Self {
+        date.to_string()
+    }
+}
+
+/// Remove the dashes from an ISO 8601 date (`2025-03-31` becomes `20250331`)
+///
+/// Other values, including dates with dashes in the wrong places, are kept as is.
+pub(crate) fn normalize(date: Cow<'_, str>) -> Cow<'_, str> {
+    let b = date.as_bytes();
+    let is_iso = b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && [&b[..4], &b[5..7], &b[8..]]
+            .iter()
+            .all(|part| part.iter().all(u8::is_ascii_digit));
+
+    if !is_iso {
+        return date;
+    }
+
+    Cow::Owned(date.replace('-', ""))
+}
+
+/// Check that a year, month and day form a real calendar date
+pub(crate) fn is_valid_date(year: u16, month: u8, day: u8) -> bool {
+    (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)
+}
+
+/// Number of days in a month (1-12) of the Gregorian calendar
+pub(crate) fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn is_leap_year(year: u16) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn spayd_date_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .date(SpaydDate::new(2025, 3, 5).unwrap())
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*DT:20250305"
+        );
+
+        let date: SpaydDate = "20240229".parse().unwrap();
+
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 2, 29));
+        assert_eq!(date.to_string(), "20240229");
+    }
+
+    #[test]
+    fn add_days_works() {
+        let date = |year, month, day| SpaydDate::new(year, month, day).unwrap();
+
+        assert_eq!(date(2025, 3, 5).add_days(14), Ok(date(2025, 3, 19)));
+        assert_eq!(date(2025, 1, 25).add_days(14), Ok(date(2025, 2, 8)));
+        assert_eq!(date(2024, 12, 25).add_days(14), Ok(date(2025, 1, 8)));
+        assert_eq!(date(2024, 2, 20).add_days(14), Ok(date(2024, 3, 5)));
+        assert_eq!(date(2023, 2, 20).add_days(14), Ok(date(2023, 3, 6)));
+        assert_eq!(date(2024, 2, 28).add_days(1), Ok(date(2024, 2, 29)));
+        assert_eq!(date(2024, 1, 1).add_days(366), Ok(date(2025, 1, 1)));
+        assert_eq!(date(2025, 1, 1).add_days(0), Ok(date(2025, 1, 1)));
+        assert_eq!(date(2000, 1, 1).add_days(u16::MAX), Ok(date(2179, 6, 6)));
+        assert_eq!(
+            date(2999, 12, 31).add_days(1),
+            Err(SpaydError::InvalidDate("Year is out of range 1000-2999"))
+        );
+    }
+
+    #[test]
+    fn due_in_days_works() {
+        let spayd = |today: SpaydDate, days: u16| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .due_in_days_from(today, days)
+                .build()
+        };
+        let date = |year, month, day| SpaydDate::new(year, month, day).unwrap();
+
+        for (today, days, due) in [
+            (date(2025, 3, 10), 14, "20250324"),
+            (date(2025, 1, 25), 14, "20250208"),
+            (date(2024, 12, 25), 14, "20250108"),
+            (date(2024, 2, 20), 14, "20240305"),
+            (date(2023, 2, 20), 14, "20230306"),
+        ] {
+            assert_eq!(spayd(today, days).get(SpaydKey::Dt).as_deref(), Some(due));
+        }
+
+        assert_eq!(
+            spayd(date(2999, 12, 31), 1).validate(),
+            Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format"))
+        );
+    }
+
+    #[test]
+    fn reject_past_dates_works() {
+        let today = SpaydDate::new(2025, 3, 31).unwrap();
+        let spayd = |date: &str, reject_past_dates| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .date(date)
+                .validation(ValidationOptions {
+                    reject_past_dates,
+                    today: Some(today),
+                    ..Default::default()
+                })
+                .build()
+                .spayd_string()
+        };
+
+        assert!(spayd("20250331", true).is_ok());
+        assert!(spayd("20250401", true).is_ok());
+        assert!(spayd("20240331", false).is_ok());
+
+        let result = spayd("20250330", true);
+
+        assert_eq!(
+            result,
+            Err(SpaydError::DateInPast {
+                date: SpaydDate::new(2025, 3, 30).unwrap(),
+                today,
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid DT: Date 20250330 is before today (20250331)"
+        );
+    }
+
+    #[test]
+    fn iso_date_is_normalized() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .date("2025-03-31")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*DT:20250331"
+        );
+
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        spayd.set(SpaydKey::Dt, "2024-02-29").unwrap();
+        assert_eq!(spayd.get(SpaydKey::Dt).as_deref(), Some("20240229"));
+
+        for date in [
+            "2025-3-31",
+            "2025-03-1",
+            "2025/03/31",
+            "20-25-0331",
+            "2025-02-30",
+        ] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .date(date)
+                .build();
+
+            assert!(
+                matches!(spayd.spayd_string(), Err(SpaydError::InvalidDate(_))),
+                "{}",
+                date
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_spayd_date_fails() {
+        assert_eq!(
+            SpaydDate::new(2023, 2, 29),
+            Err(SpaydError::InvalidDate("Date does not exist"))
+        );
+        assert_eq!(
+            SpaydDate::new(2025, 0, 1),
+            Err(SpaydError::InvalidDate("Date does not exist"))
+        );
+        assert_eq!(
+            SpaydDate::new(999, 1, 1),
+            Err(SpaydError::InvalidDate("Year is out of range 1000-2999"))
+        );
+        assert_eq!(
+            "2025035".parse::<SpaydDate>(),
+            Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format"))
+        );
+    }
+
+    #[test]
+    fn calendar_date_works() {
+        let spayd = |date: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .date(date.to_string())
+                .build()
+                .spayd_string()
+        };
+
+        for date in ["20240229", "20000229", "20231231", "20230131", "19000228"] {
+            assert!(spayd(date).is_ok(), "{}", date);
+        }
+
+        for date in [
+            "20230229", "19000229", "20230230", "20231131", "20230431", "20231301",
+        ] {
+            assert_eq!(
+                spayd(date),
+                Err(SpaydError::InvalidDate("Date does not exist")),
+                "{}",
+                date
+            );
+        }
+
+        for date in ["2023-8-10", "2023081", "30230810", "0230810X"] {
+            assert_eq!(
+                spayd(date),
+                Err(SpaydError::InvalidDate("Date is not in YYYYMMDD format")),
+                "{}",
+                date
+            );
+        }
+    }
+}
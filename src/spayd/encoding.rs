@@ -0,0 +1,327 @@
+use std::borrow::Cow;
+
+/// Invalid percent-escape found while decoding a value
+#[derive(Debug, PartialEq)]
+pub(crate) struct InvalidEscape {
+    /// Offending sequence
+    pub sequence: String,
+
+    /// Byte offset of the sequence within the value
+    pub offset: usize,
+}
+
+/// How characters outside the allowed character set are handled in `RN`, `MSG` and `PT`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EscapePolicy {
+    /// Fail validation
+    #[default]
+    Error,
+
+    /// Percent-encode them as UTF-8 (`*` becomes `%2A`, `č` becomes `%C4%8D`)
+    ///
+    /// `%` and control characters are then percent-encoded in all values too, so every value
+    /// (e.g. a message containing `%2A`) decodes back unchanged.
+    PercentEncode,
+
+    /// Leave them out
+    Strip,
+}
+
+impl EscapePolicy {
+    /// Write a value according to the policy
+    ///
+    /// With [`EscapePolicy::Error`], `*` and `%` starting an escape are still percent-encoded,
+    /// so the payload can always be split into fields and decodes back unchanged.
+    pub(crate) fn apply(self, value: &str) -> Cow<'_, str> {
+        let keep = |c: char| is_allowed(c) && (c != '%' || self == EscapePolicy::Strip);
+
+        if self == EscapePolicy::Error || value.chars().all(keep) {
+            return encode(value);
+        }
+
+        let mut escaped = String::with_capacity(value.len());
+
+        for c in value.chars() {
+            if keep(c) {
+                escaped.push(c);
+            } else if self == EscapePolicy::PercentEncode {
+                let mut buf = [0; 4];
+
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    escaped.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+
+        match self {
+            EscapePolicy::Strip => Cow::Owned(encode(&escaped).into_owned()),
+            _ => Cow::Owned(escaped),
+        }
+    }
+}
+
+/// Check that a character is allowed by the specification
+pub(crate) fn is_allowed(c: char) -> bool {
+    matches!(c, '0'..='9' | 'A'..='Z' | ' ' | '$' | '%' | '+' | '-' | '.' | '/' | ':')
+}
+
+/// Percent-encode characters that would break the `KEY:VALUE*KEY:VALUE` structure, and `%`
+/// where [`decode`] would read it as an escape (`10%20` becomes `10%2520`)
+///
+/// Any other `%` (e.g. `"10 % OFF"`) is written as is.
+pub(crate) fn encode(value: &str) -> Cow<'_, str> {
+    let bytes = value.as_bytes();
+    let starts_escape =
+        |i: usize| bytes[i] == b'%' && bytes.get(i + 1).is_some_and(u8::is_ascii_alphanumeric);
+
+    if !value.contains('*') && !(0..bytes.len()).any(starts_escape) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut encoded = String::with_capacity(value.len());
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '*' => encoded.push_str("%2A"),
+            '%' if starts_escape(i) => encoded.push_str("%25"),
+            c => encoded.push(c),
+        }
+    }
+
+    Cow::Owned(encoded)
+}
+
+/// Percent-encode `%`, `*` and control characters, so that the value always decodes back
+/// unchanged
+pub(crate) fn encode_canonical(value: &str) -> Cow<'_, str> {
+    if !value.contains(['%', '*']) && !value.contains(char::is_control) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut encoded = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '%' => encoded.push_str("%25"),
+            '*' => encoded.push_str("%2A"),
+            c if c.is_control() => {
+                let mut buf = [0; 4];
+
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    encoded.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            c => encoded.push(c),
+        }
+    }
+
+    Cow::Owned(encoded)
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters
+pub(crate) fn encode_uri_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Form-urlencode a value: space becomes `+`, everything except RFC 3986 unreserved
+/// characters is percent-encoded
+pub(crate) fn encode_form_component(value: &str) -> String {
+    encode_uri_component(value).replace("%20", "+")
+}
+
+/// Decode a form-urlencoded (`application/x-www-form-urlencoded`) component
+///
+/// `+` is decoded as a space. Invalid escapes are kept as is and invalid UTF-8 is
+/// replaced, as browsers do.
+pub(crate) fn decode_form_component(value: &str) -> Cow<'_, str> {
+    if !value.contains(['+', '%']) {
+        return Cow::Borrowed(value);
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+
+        match (bytes[i], hex) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Decode percent-escapes (`%2A`, `%C5%BD`, ...) in a value
+///
+/// A `%` followed by an alphanumeric character starts an escape, which must
+/// consist of two hex digits. Any other `%` (e.g. `"10 % OFF"`) is kept as is,
+/// so payloads from generators that don't encode `%` can still be read.
+pub(crate) fn decode(value: &str) -> Result<Cow<'_, str>, InvalidEscape> {
+    if !value.contains('%') {
+        return Ok(Cow::Borrowed(value));
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && bytes.get(i + 1).is_some_and(u8::is_ascii_alphanumeric) {
+            let hex = bytes.get(i + 1..i + 3).and_then(|hex| {
+                let hex = std::str::from_utf8(hex).ok()?;
+                u8::from_str_radix(hex, 16).ok()
+            });
+
+            match hex {
+                Some(byte) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                None => {
+                    let end = (i + 3).min(bytes.len());
+                    return Err(InvalidEscape {
+                        sequence: String::from_utf8_lossy(&bytes[i..end]).into_owned(),
+                        offset: i,
+                    });
+                }
+            }
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded)
+        .map(Cow::Owned)
+        .map_err(|_| InvalidEscape {
+            sequence: value.to_string(),
+            offset: 0,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_works() {
+        assert_eq!(decode("PLATBA%20ZA%20ZBOZI"), Ok("PLATBA ZA ZBOZI".into()));
+        assert_eq!(decode("A%2AB"), Ok("A*B".into()));
+        assert_eq!(decode("%C5%BDLUT%C3%9D"), Ok("ŽLUTÝ".into()));
+        assert_eq!(decode("MISTR1/+.% PO:"), Ok("MISTR1/+.% PO:".into()));
+        assert_eq!(decode("100%"), Ok("100%".into()));
+    }
+
+    #[test]
+    fn decode_invalid_escape_fails() {
+        assert_eq!(
+            decode("AB%G1"),
+            Err(InvalidEscape {
+                sequence: "%G1".to_string(),
+                offset: 2
+            })
+        );
+        assert_eq!(
+            decode("AB%2"),
+            Err(InvalidEscape {
+                sequence: "%2".to_string(),
+                offset: 2
+            })
+        );
+        assert_eq!(
+            decode("%C5"),
+            Err(InvalidEscape {
+                sequence: "%C5".to_string(),
+                offset: 0
+            })
+        );
+    }
+
+    #[test]
+    fn encode_round_trip_works() {
+        assert_eq!(encode("A*B"), "A%2AB");
+        assert_eq!(decode(&encode("A*B*C")), Ok("A*B*C".into()));
+        assert_eq!(encode("10 % OFF"), "10 % OFF");
+        assert_eq!(encode("SLEVA 10%20"), "SLEVA 10%2520");
+
+        for value in ["SLEVA 10%20", "%2A", "100%FF", "ABC%AB", "%G1", "100%"] {
+            assert_eq!(decode(&encode(value)), Ok(value.into()));
+        }
+    }
+
+    #[test]
+    fn encode_uri_component_works() {
+        assert_eq!(encode_uri_component("AZaz09-._~"), "AZaz09-._~");
+        assert_eq!(encode_uri_component("SPD*1.0*AM:5"), "SPD%2A1.0%2AAM%3A5");
+        assert_eq!(encode_uri_component("10 % Ž"), "10%20%25%20%C5%BD");
+    }
+
+    #[test]
+    fn encode_form_component_works() {
+        assert_eq!(encode_form_component("PLATBA ZA ZBOZI"), "PLATBA+ZA+ZBOZI");
+        assert_eq!(encode_form_component("1+1 = 2 %"), "1%2B1+%3D+2+%25");
+        assert_eq!(
+            decode_form_component(&encode_form_component("ŽLUTÝ + 10 %")),
+            "ŽLUTÝ + 10 %"
+        );
+    }
+
+    #[test]
+    fn decode_form_component_works() {
+        assert_eq!(decode_form_component("PLATBA+ZA+ZBOZI"), "PLATBA ZA ZBOZI");
+        assert_eq!(decode_form_component("1%2B1%3D2"), "1+1=2");
+        assert_eq!(decode_form_component("%C5%BDLUT%C3%9D"), "ŽLUTÝ");
+        assert_eq!(decode_form_component("100%"), "100%");
+        assert_eq!(decode_form_component("%G1"), "%G1");
+    }
+
+    #[test]
+    fn escape_policy_works() {
+        assert_eq!(EscapePolicy::Error.apply("A*B č"), "A%2AB č");
+        assert_eq!(EscapePolicy::PercentEncode.apply("A*B č"), "A%2AB %C4%8D");
+        assert_eq!(EscapePolicy::PercentEncode.apply("10 %2A"), "10 %252A");
+        assert_eq!(EscapePolicy::Strip.apply("10 % *"), "10 % ");
+        assert_eq!(EscapePolicy::Strip.apply("A*B č"), "AB ");
+        assert_eq!(EscapePolicy::Strip.apply("10%2A č"), "10%252A ");
+        assert_eq!(
+            decode(&EscapePolicy::PercentEncode.apply("Žluťoučký*kůň")),
+            Ok("Žluťoučký*kůň".into())
+        );
+    }
+
+    #[test]
+    fn encode_canonical_works() {
+        assert_eq!(encode_canonical("10 % OFF"), "10 %25 OFF");
+        assert_eq!(encode_canonical("%2A*"), "%252A%2A");
+        assert_eq!(decode(&encode_canonical("%2A*")), Ok("%2A*".into()));
+        assert_eq!(encode_canonical("A\tB\n"), "A%09B%0A");
+        assert_eq!(decode(&encode_canonical("A\tB\n")), Ok("A\tB\n".into()));
+    }
+}
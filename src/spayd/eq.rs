@@ -0,0 +1,100 @@
+use super::*;
+
+impl Spayd {
+    /// Compare two payments ignoring differences in formatting
+    ///
+    /// The account is compared without whitespace and case, amounts numerically (`5` equals
+    /// `5.00`), fields in any order, and an unset currency equals `CZK`.
+    pub fn semantically_eq(&self, other: &Spayd) -> bool {
+        self.semantically_eq_with(other, Some("CZK"))
+    }
+
+    /// Like [`Spayd::semantically_eq`], with the currency assumed when `CC` is unset
+    ///
+    /// With `None`, an unset currency only equals another unset currency.
+    pub fn semantically_eq_with(&self, other: &Spayd, default_currency: Option<&str>) -> bool {
+        self.semantic_fields(default_currency) == other.semantic_fields(default_currency)
+    }
+
+    /// Fields in a normal form, sorted by key
+    fn semantic_fields(&self, default_currency: Option<&str>) -> Vec<(SpaydKey, String)> {
+        let mut fields: Vec<_> = self
+            .iter()
+            .map(|(key, value)| {
+                let value = match key {
+                    SpaydKey::Acc => iban::normalize(value).into_owned(),
+                    SpaydKey::Am => numeric_amount(&value).unwrap_or_else(|| value.into_owned()),
+                    SpaydKey::Cc => value.to_ascii_uppercase(),
+                    _ => value.into_owned(),
+                };
+
+                (key, value)
+            })
+            .collect();
+
+        if let (None, Some(currency)) = (&self.currency, default_currency) {
+            fields.push((SpaydKey::Cc, currency.to_ascii_uppercase()));
+        }
+
+        fields.sort();
+        fields
+    }
+}
+
+/// Amount without leading zeros of the integer part and trailing zeros of the fraction
+fn numeric_amount(amount: &str) -> Option<String> {
+    let (int, frac) = amount.split_once('.').unwrap_or((amount, ""));
+
+    if int.is_empty() || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+
+    Some(format!(
+        "{}.{}",
+        if int.is_empty() { "0" } else { int },
+        frac
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn semantically_eq_works() {
+        let a =
+            Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:5*MSG:PLATBA*X-VS:12").unwrap();
+        let b = Spayd::parse(
+            "SPD*1.0*X-VS:12*MSG:PLATBA*AM:005.00*CC:CZK*ACC:cz55 0800 0000 0012 3456 7899",
+        )
+        .unwrap();
+
+        assert!(a.semantically_eq(&b));
+        assert!(b.semantically_eq(&a));
+        assert!(!a.semantically_eq_with(&b, None));
+    }
+
+    #[test]
+    fn semantically_different_fails() {
+        let a = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:5*MSG:PLATBA").unwrap();
+
+        for other in [
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:5*MSG:PLATBA",
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5.01*MSG:PLATBA",
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:50*MSG:PLATBA",
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5*MSG:PLATBA 2",
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5*MSG:PLATBA*CC:EUR",
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5*MSG:PLATBA*X-VS:12",
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:PLATBA",
+        ] {
+            assert!(
+                !a.semantically_eq(&Spayd::parse(other).unwrap()),
+                "{}",
+                other
+            );
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{czech, SpaydError, ValidationOptions};
+
+/// IBAN lengths by country code, as published in the SWIFT IBAN registry
+const LENGTHS: [(&str, usize); 88] = [
+    ("AD", 24),
+    ("AE", 23),
+    ("AL", 28),
+    ("AT", 20),
+    ("AZ", 28),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BI", 27),
+    ("BR", 29),
+    ("BY", 28),
+    ("CH", 21),
+    ("CR", 22),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DJ", 27),
+    ("DK", 18),
+    ("DO", 28),
+    ("EE", 20),
+    ("EG", 29),
+    ("ES", 24),
+    ("FI", 18),
+    ("FK", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GE", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("GT", 28),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IQ", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("JO", 30),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LC", 32),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("LY", 25),
+    ("MC", 27),
+    ("MD", 24),
+    ("ME", 22),
+    ("MK", 19),
+    ("MN", 20),
+    ("MR", 27),
+    ("MT", 31),
+    ("MU", 30),
+    ("NI", 28),
+    ("NL", 18),
+    ("NO", 15),
+    ("OM", 23),
+    ("PK", 24),
+    ("PL", 28),
+    ("PS", 29),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("RU", 33),
+    ("SA", 24),
+    ("SC", 31),
+    ("SD", 18),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("SO", 23),
+    ("ST", 25),
+    ("SV", 28),
+    ("TL", 23),
+    ("TN", 24),
+    ("TR", 26),
+    ("UA", 29),
+    ("VA", 22),
+    ("VG", 24),
+    ("XK", 20),
+    ("YE", 30),
+];
+
+/// IBAN in the electronic format with a valid structure, length and check digits
+///
+/// Display writes the electronic format (`CZ6508000000192000145399`), the alternate
+/// flag (`{:#}`) the paper format in blocks of four (`CZ65 0800 0000 1920 0014 5399`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Iban(String);
+
+impl Iban {
+    /// Parse an IBAN written in the electronic or the paper format
+    pub fn parse(iban: &str) -> Result<Self, SpaydError> {
+        let iban = normalize(iban.into());
+
+        validate(&iban, &ValidationOptions::default())?;
+
+        Ok(Iban(iban.into_owned()))
+    }
+
+    /// Country code (e.g. `CZ`)
+    pub fn country_code(&self) -> &str {
+        &self.0[..2]
+    }
+
+    /// Check digits (e.g. `65`)
+    pub fn check_digits(&self) -> &str {
+        &self.0[2..4]
+    }
+
+    /// Basic bank account number, the country-specific part after the check digits
+    pub fn bban(&self) -> &str {
+        &self.0[4..]
+    }
+
+    /// IBAN in the electronic format
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Iban {
+    type Err = SpaydError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Iban::parse(s)
+    }
+}
+
+impl AsRef<str> for Iban {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Iban> for String {
+    fn from(iban: Iban) -> Self {
+        iban.0
+    }
+}
+
+impl fmt::Display for Iban {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return f.write_str(&self.0);
+        }
+
+        for (i, block) in self.0.as_bytes().chunks(4).enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+
+            f.write_str(std::str::from_utf8(block).expect("IBAN is ASCII"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Validate an IBAN in the electronic format
+pub(crate) fn validate(iban: &str, options: &ValidationOptions) -> Result<(), SpaydError> {
+    if !format_valid(iban) {
+        return Err(SpaydError::InvalidAccountNumber(
+            "Value is not a valid IBAN",
+        ));
+    }
+
+    let country = [iban.as_bytes()[0], iban.as_bytes()[1]];
+    let profile_countries = options
+        .profile
+        .as_ref()
+        .and_then(|profile| profile.allowed_countries.as_deref());
+
+    if options
+        .allowed_countries
+        .as_ref()
+        .is_some_and(|allowed| !allowed.contains(&country))
+        || profile_countries.is_some_and(|allowed| !allowed.contains(&country))
+    {
+        return Err(SpaydError::CountryNotAllowed { country });
+    }
+
+    match country_length(iban) {
+        Some((country, expected)) if iban.len() != expected => {
+            return Err(SpaydError::InvalidIbanLength {
+                country,
+                expected,
+                actual: iban.len(),
+            });
+        }
+        None if options.reject_unknown_iban_countries || options.strict() => {
+            return Err(SpaydError::InvalidAccountNumber(
+                "Unknown IBAN country code",
+            ));
+        }
+        _ => {}
+    }
+
+    if !checksum_valid(iban) {
+        return Err(SpaydError::InvalidAccountNumber(
+            "IBAN check digits do not match",
+        ));
+    }
+
+    if (options.check_czech_account || options.strict()) && iban.starts_with("CZ") {
+        let (prefix, number) = iban[8..].split_at(6);
+
+        if !czech::mod11_valid(prefix) || !czech::mod11_valid(number) {
+            return Err(SpaydError::InvalidAccountNumber(
+                "Czech account number fails modulo-11 check",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Country code, two check digits and up to 30 uppercase letters or digits
+fn format_valid(iban: &str) -> bool {
+    let bytes = iban.as_bytes();
+
+    (5..=34).contains(&bytes.len())
+        && bytes[..2].iter().all(u8::is_ascii_uppercase)
+        && bytes[2..4].iter().all(u8::is_ascii_digit)
+        && bytes[4..]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+/// Convert an IBAN to the electronic format: no whitespace, uppercase
+///
+/// Other separators (e.g. `-`) are kept, so they still fail validation.
+pub(crate) fn normalize(iban: Cow<'_, str>) -> Cow<'_, str> {
+    if !iban
+        .chars()
+        .any(|c| c.is_whitespace() || c.is_ascii_lowercase())
+    {
+        return iban;
+    }
+
+    Cow::Owned(
+        iban.chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect(),
+    )
+}
+
+/// Split an `ACC` value written as `IBAN+BIC` into its parts
+pub(crate) fn split_bic(account: Cow<'_, str>) -> (Cow<'_, str>, Option<Cow<'_, str>>) {
+    match account {
+        Cow::Borrowed(account) => match account.split_once('+') {
+            Some((iban, bic)) => (Cow::Borrowed(iban), Some(Cow::Borrowed(bic))),
+            None => (Cow::Borrowed(account), None),
+        },
+        Cow::Owned(account) => match account.split_once('+') {
+            Some((iban, bic)) => (
+                Cow::Owned(iban.to_string()),
+                Some(Cow::Owned(bic.to_string())),
+            ),
+            None => (Cow::Owned(account), None),
+        },
+    }
+}
+
+/// Country code and expected IBAN length for the country `iban` belongs to, if known
+pub(crate) fn country_length(iban: &str) -> Option<(&'static str, usize)> {
+    let country = iban.get(..2)?;
+
+    LENGTHS.iter().find(|(code, _)| *code == country).copied()
+}
+
+/// Verify the ISO 13616 mod-97 check digits of an IBAN in electronic format
+///
+/// The first four characters are moved to the end, letters are replaced with
+/// numbers (`A` = 10, ..., `Z` = 35) and the resulting number must give remainder 1
+/// when divided by 97.
+pub(crate) fn checksum_valid(iban: &str) -> bool {
+    if iban.len() < 5 || !iban.is_ascii() {
+        return false;
+    }
+
+    let (head, tail) = iban.split_at(4);
+
+    mod97(tail.chars().chain(head.chars())) == Some(1)
+}
+
+/// Compute the check digits of an IBAN with the given country code and BBAN
+pub(crate) fn check_digits(country: &str, bban: &str) -> Option<String> {
+    let remainder = mod97(bban.chars().chain(country.chars()).chain("00".chars()))?;
+
+    Some(format!("{:02}", 98 - remainder))
+}
+
+/// Remainder of the number written by `chars` (letters as `A` = 10, ..., `Z` = 35)
+/// divided by 97, computed digit by digit so no big integers are needed
+fn mod97(chars: impl Iterator<Item = char>) -> Option<u32> {
+    let mut remainder = 0u32;
+
+    for c in chars {
+        let value = c.to_digit(36)?;
+
+        remainder = if value < 10 {
+            (remainder * 10 + value) % 97
+        } else {
+            (remainder * 100 + value) % 97
+        };
+    }
+
+    Some(remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iban_works() {
+        let iban = Iban::parse("cz65 0800 0000 1920 0014 5399").unwrap();
+
+        assert_eq!(iban.country_code(), "CZ");
+        assert_eq!(iban.check_digits(), "65");
+        assert_eq!(iban.bban(), "08000000192000145399");
+        assert_eq!(iban.to_string(), "CZ6508000000192000145399");
+        assert_eq!(format!("{:#}", iban), "CZ65 0800 0000 1920 0014 5399");
+
+        let iban: Iban = "GB29NWBK60161331926819".parse().unwrap();
+
+        assert_eq!(format!("{:#}", iban), "GB29 NWBK 6016 1331 9268 19");
+    }
+
+    #[test]
+    fn invalid_iban_fails() {
+        assert_eq!(
+            Iban::parse("CZ6508000000192000145398"),
+            Err(SpaydError::InvalidAccountNumber(
+                "IBAN check digits do not match"
+            ))
+        );
+        assert_eq!(
+            Iban::parse("CZ65-0800-0000-1920-0014-5399"),
+            Err(SpaydError::InvalidAccountNumber(
+                "Value is not a valid IBAN"
+            ))
+        );
+        assert_eq!(
+            Iban::parse("CZ650800000019200014539"),
+            Err(SpaydError::InvalidIbanLength {
+                country: "CZ",
+                expected: 24,
+                actual: 23,
+            })
+        );
+    }
+
+    #[test]
+    fn checksum_valid_works() {
+        assert!(checksum_valid("CZ6508000000192000145399"));
+        assert!(checksum_valid("CZ5508000000001234567899"));
+        assert!(checksum_valid("SK3112000000198742637541"));
+        assert!(checksum_valid("DE89370400440532013000"));
+        assert!(checksum_valid("GB29NWBK60161331926819"));
+    }
+
+    #[test]
+    fn check_digits_works() {
+        assert_eq!(
+            check_digits("CZ", "08000000192000145399").as_deref(),
+            Some("65")
+        );
+        assert_eq!(
+            check_digits("GB", "NWBK60161331926819").as_deref(),
+            Some("29")
+        );
+        assert_eq!(check_digits("CZ", "0800-0001").as_deref(), None);
+    }
+
+    #[test]
+    fn normalize_works() {
+        assert_eq!(
+            normalize("CZ55 0800 0000 0012 3456 7899".into()),
+            "CZ5508000000001234567899"
+        );
+        assert_eq!(
+            normalize(" cz5508000000001234567899\t".into()),
+            "CZ5508000000001234567899"
+        );
+        assert_eq!(
+            normalize("CZ55-0800-0000-0012-3456-7899".into()),
+            "CZ55-0800-0000-0012-3456-7899"
+        );
+        assert!(matches!(
+            normalize("CZ5508000000001234567899".into()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn split_bic_works() {
+        assert_eq!(
+            split_bic("CZ5855000000001265098001+RZBCCZPP".into()),
+            ("CZ5855000000001265098001".into(), Some("RZBCCZPP".into()))
+        );
+        assert_eq!(
+            split_bic("CZ5855000000001265098001".into()),
+            ("CZ5855000000001265098001".into(), None)
+        );
+        assert_eq!(
+            split_bic("+RZBCCZPP".into()),
+            ("".into(), Some("RZBCCZPP".into()))
+        );
+    }
+
+    #[test]
+    fn country_length_works() {
+        assert_eq!(country_length("CZ6508000000192000145399"), Some(("CZ", 24)));
+        assert_eq!(country_length("GB29NWBK60161331926819"), Some(("GB", 22)));
+        assert_eq!(country_length("XX29NWBK60161331926819"), None);
+        assert_eq!(country_length(""), None);
+    }
+
+    #[test]
+    fn checksum_invalid_fails() {
+        assert!(!checksum_valid("CZ0000000000000000000000"));
+        assert!(!checksum_valid("CZ6508000000192000145398"));
+        assert!(!checksum_valid("SK3112000000198742637540"));
+        assert!(!checksum_valid("DE88370400440532013000"));
+        assert!(!checksum_valid("GB29NWBK60161331926818"));
+        assert!(!checksum_valid("CZ65"));
+    }
+}
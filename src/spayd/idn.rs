@@ -0,0 +1,160 @@
+use std::borrow::Cow;
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Write the non-ASCII labels of an email domain in their ASCII form (`jan@příklad.cz` becomes
+/// `jan@xn--pklad-zsa96e.cz`)
+///
+/// The local part is left as is. Labels that cannot be encoded are kept, so validation rejects
+/// them.
+pub(crate) fn email_to_ascii(address: Cow<'_, str>) -> Cow<'_, str> {
+    let Some((local, domain)) = address.rsplit_once('@') else {
+        return address;
+    };
+
+    if domain.is_ascii() {
+        return address;
+    }
+
+    let domain: Vec<String> = domain
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                return label.to_string();
+            }
+
+            match punycode(&label.to_lowercase()) {
+                Some(encoded) => format!("xn--{}", encoded),
+                None => label.to_string(),
+            }
+        })
+        .collect();
+
+    Cow::Owned(format!("{}@{}", local, domain.join(".")))
+}
+
+/// Punycode encoding of a label as described in RFC 3492
+fn punycode(label: &str) -> Option<String> {
+    let input: Vec<u32> = label.chars().map(u32::from).collect();
+    let mut output: String = label.chars().filter(char::is_ascii).collect();
+
+    let basic = output.len() as u32;
+    let mut handled = basic;
+
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let (mut n, mut delta, mut bias) = (INITIAL_N, 0u32, INITIAL_BIAS);
+
+    while (handled as usize) < input.len() {
+        let m = input.iter().copied().filter(|&c| c >= n).min()?;
+
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+
+                loop {
+                    let t = (k.saturating_sub(bias)).clamp(T_MIN, T_MAX);
+
+                    if q < t {
+                        break;
+                    }
+
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(output)
+}
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = delta / if first_time { DAMP } else { 2 };
+    delta += delta / num_points;
+
+    let mut k = 0;
+
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn digit(d: u32) -> char {
+    match d {
+        0..=25 => char::from(b'a' + d as u8),
+        _ => char::from(b'0' + (d - 26) as u8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn idn_email_is_written_as_ascii() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_email("jan@příklad.cz")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:jan@xn--pklad-zsa96e.cz"
+        );
+        assert_eq!(
+            spayd.canonical_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:jan@xn--pklad-zsa96e.cz"
+        );
+        assert_eq!(spayd.get(SpaydKey::Nta).as_deref(), Some("jan@příklad.cz"));
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_email("Jan@München.DE")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:Jan@xn--mnchen-3ya.DE"
+        );
+    }
+
+    #[test]
+    fn idn_email_local_part_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_email("jän@example.cz")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress("Invalid email address"))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+
+use super::*;
+
+/// Iterator over the set fields of a [`Spayd`], created by [`Spayd::iter`]
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    spayd: &'a Spayd,
+    keys: std::slice::Iter<'static, SpaydKey>,
+    extras: std::slice::Iter<'a, (String, String)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (SpaydKey, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let spayd = self.spayd;
+
+        self.keys
+            .find_map(|key| spayd.get(key.clone()).map(|value| (key.clone(), value)))
+            .or_else(|| {
+                self.extras.next().map(|(key, value)| {
+                    (SpaydKey::Custom(key.clone()), Cow::Borrowed(value.as_str()))
+                })
+            })
+    }
+}
+
+impl<'a> IntoIterator for &'a Spayd {
+    type Item = (SpaydKey, Cow<'a, str>);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl Spayd {
+    /// Iterate over set fields as (key, raw value) pairs, in output order
+    ///
+    /// Payment and notify types are rendered to their codes (e.g. `IP`, `E`). Fields not
+    /// modelled by this crate come last, as [`SpaydKey::Custom`].
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            spayd: self,
+            keys: SpaydKey::ALL.iter(),
+            extras: self.extras.iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn iter_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .payment_type(PaymentType::Instant)
+            .message("PAYMENT".to_string())
+            .notify_email("email@example.com")
+            .extras(vec![("X-VS".to_string(), "1234".to_string())])
+            .build();
+
+        let fields: Vec<(SpaydKey, String)> = spayd
+            .iter()
+            .map(|(key, value)| (key, value.into_owned()))
+            .collect();
+
+        assert_eq!(
+            fields,
+            [
+                (SpaydKey::Acc, "CZ5508000000001234567899".to_string()),
+                (SpaydKey::Am, "239.50".to_string()),
+                (SpaydKey::Pt, "IP".to_string()),
+                (SpaydKey::Msg, "PAYMENT".to_string()),
+                (SpaydKey::Nt, "E".to_string()),
+                (SpaydKey::Nta, "email@example.com".to_string()),
+                (SpaydKey::Custom("X-VS".to_string()), "1234".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .currency("CZK".to_string())
+            .build();
+
+        let mut keys = Vec::new();
+
+        for (key, _) in &spayd {
+            keys.push(key);
+        }
+
+        assert_eq!(keys, [SpaydKey::Acc, SpaydKey::Am, SpaydKey::Cc]);
+    }
+}
@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use super::SpaydError;
+
+/// SPAYD field key
+///
+/// Keys are ordered as in the canonical output: alphabetically by name, custom keys
+/// included. A custom key with the name of a modelled key sorts right after it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SpaydKey {
+    /// Account number (`ACC`)
+    Acc,
+
+    /// Alternative accounts (`ALT-ACC`)
+    AltAcc,
+
+    /// Amount (`AM`)
+    Am,
+
+    /// Currency (`CC`)
+    Cc,
+
+    /// Reference (`RF`)
+    Rf,
+
+    /// Recipient name (`RN`)
+    Rn,
+
+    /// Due date (`DT`)
+    Dt,
+
+    /// Payment type (`PT`)
+    Pt,
+
+    /// Message (`MSG`)
+    Msg,
+
+    /// Notify type (`NT`)
+    Nt,
+
+    /// Notify address (`NTA`)
+    Nta,
+
+    /// Field not modelled by this crate (e.g. `X-VS`)
+    Custom(String),
+}
+
+impl SpaydKey {
+    /// All modelled keys, in output order
+    pub(crate) const ALL: [SpaydKey; 11] = [
+        SpaydKey::Acc,
+        SpaydKey::AltAcc,
+        SpaydKey::Am,
+        SpaydKey::Cc,
+        SpaydKey::Rf,
+        SpaydKey::Rn,
+        SpaydKey::Dt,
+        SpaydKey::Pt,
+        SpaydKey::Msg,
+        SpaydKey::Nt,
+        SpaydKey::Nta,
+    ];
+
+    /// Key as it appears in the SPAYD string
+    pub fn as_str(&self) -> &str {
+        match self {
+            SpaydKey::Acc => "ACC",
+            SpaydKey::AltAcc => "ALT-ACC",
+            SpaydKey::Am => "AM",
+            SpaydKey::Cc => "CC",
+            SpaydKey::Rf => "RF",
+            SpaydKey::Rn => "RN",
+            SpaydKey::Dt => "DT",
+            SpaydKey::Pt => "PT",
+            SpaydKey::Msg => "MSG",
+            SpaydKey::Nt => "NT",
+            SpaydKey::Nta => "NTA",
+            SpaydKey::Custom(key) => key,
+        }
+    }
+
+    /// Look up a modelled key by name, ignoring case
+    pub(crate) fn from_name(name: &str) -> Option<SpaydKey> {
+        SpaydKey::ALL
+            .into_iter()
+            .find(|key| key.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// Check that a field not modelled by this crate can be written and read back under its name
+    pub(crate) fn validate_custom(name: &str) -> Result<(), SpaydError> {
+        let reason = if name.is_empty() {
+            "Name is empty"
+        } else if name.contains(['*', ':']) {
+            "Name contains * or :"
+        } else if SpaydKey::from_name(name).is_some() {
+            "Name of a field modelled by this crate"
+        } else {
+            return Ok(());
+        };
+
+        Err(SpaydError::InvalidCustomKey {
+            key: SpaydKey::Custom(name.to_string()),
+            reason,
+        })
+    }
+}
+
+impl fmt::Display for SpaydKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Ord for SpaydKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let custom = |key: &SpaydKey| matches!(key, SpaydKey::Custom(_));
+
+        // a custom key may have the name of a modelled one, which then sorts first
+        self.as_str()
+            .cmp(other.as_str())
+            .then_with(|| custom(self).cmp(&custom(other)))
+    }
+}
+
+impl PartialOrd for SpaydKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Order of the fields in the generated string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldOrder {
+    /// `ACC`, `ALT-ACC`, `AM`, `CC`, `RF`, `RN`, `DT`, `PT`, `MSG`, `NT`, `NTA`, then fields not
+    /// modelled by this crate in the order they were added
+    #[default]
+    Conventional,
+
+    /// Sorted alphabetically by key, including the fields not modelled by this crate, as the
+    /// specification defines for CRC computation
+    SpecCanonical,
+}
+
+/// Error returned when a string is not a valid SPAYD key
+#[derive(Debug, PartialEq)]
+pub struct ParseKeyError(String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid key {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+impl FromStr for SpaydKey {
+    type Err = ParseKeyError;
+
+    /// Parse key, ignoring case; names other than modelled fields become [`SpaydKey::Custom`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.contains(['*', ':']) {
+            return Err(ParseKeyError(s.to_string()));
+        }
+
+        Ok(SpaydKey::from_name(s).unwrap_or_else(|| SpaydKey::Custom(s.to_ascii_uppercase())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_works() {
+        assert_eq!("ACC".parse(), Ok(SpaydKey::Acc));
+        assert_eq!("nta".parse(), Ok(SpaydKey::Nta));
+        assert_eq!("x-vs".parse(), Ok(SpaydKey::Custom("X-VS".to_string())));
+        assert_eq!("".parse::<SpaydKey>(), Err(ParseKeyError("".to_string())));
+        assert!("A:B".parse::<SpaydKey>().is_err());
+    }
+
+    #[test]
+    fn display_works() {
+        assert_eq!(SpaydKey::Msg.to_string(), "MSG");
+        assert_eq!(SpaydKey::Custom("X-VS".to_string()).to_string(), "X-VS");
+    }
+
+    #[test]
+    fn ordering_works() {
+        let mut keys = vec![
+            SpaydKey::Custom("X-VS".to_string()),
+            SpaydKey::Rn,
+            SpaydKey::Custom("CRC32".to_string()),
+            SpaydKey::Acc,
+            SpaydKey::Custom("DL".to_string()),
+            SpaydKey::Nta,
+            SpaydKey::Am,
+            SpaydKey::Nt,
+        ];
+
+        keys.sort();
+
+        assert_eq!(
+            keys,
+            [
+                SpaydKey::Acc,
+                SpaydKey::Am,
+                SpaydKey::Custom("CRC32".to_string()),
+                SpaydKey::Custom("DL".to_string()),
+                SpaydKey::Nt,
+                SpaydKey::Nta,
+                SpaydKey::Rn,
+                SpaydKey::Custom("X-VS".to_string()),
+            ]
+        );
+
+        let custom = SpaydKey::Custom("ACC".to_string());
+
+        assert_eq!(custom.cmp(&SpaydKey::Acc), Ordering::Greater);
+        assert_eq!(SpaydKey::Acc.cmp(&custom), Ordering::Less);
+        assert_eq!(custom.cmp(&custom.clone()), Ordering::Equal);
+    }
+}
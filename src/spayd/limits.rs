@@ -0,0 +1,32 @@
+//! Maximum lengths of the fields, as enforced by validation
+
+/// Alternative accounts (`ALT-ACC`)
+pub const MAX_ALT_ACCOUNTS: usize = 2;
+
+/// Characters of the amount (`AM`)
+pub const MAX_AMOUNT_LEN: usize = 10;
+
+/// Digits of the reference (`RF`)
+pub const MAX_REFERENCE_DIGITS: usize = 16;
+
+/// Characters of the recipient name (`RN`)
+pub const MAX_RECIPIENT_CHARS: usize = 35;
+
+/// Characters of the payment type (`PT`)
+pub const MAX_PAYMENT_TYPE_CHARS: usize = 3;
+
+/// Characters of the message (`MSG`)
+pub const MAX_MESSAGE_CHARS: usize = 60;
+
+/// Characters of the notification address (`NTA`), the limit of an email address
+pub const MAX_NOTIFY_ADDRESS_CHARS: usize = 320;
+
+/// Characters of the local part of an email address in `NTA` (before the `@`)
+pub const MAX_EMAIL_LOCAL_CHARS: usize = 64;
+
+/// Characters of the domain of an email address in `NTA`, once written in ASCII
+pub const MAX_EMAIL_DOMAIN_CHARS: usize = 255;
+
+/// Characters of a phone number in `NTA`, including the `+`; the digits are limited by
+/// [`ValidationOptions::max_phone_digits`](crate::spayd::ValidationOptions::max_phone_digits)
+pub const MAX_PHONE_CHARS: usize = 20;
@@ -0,0 +1,333 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use super::*;
+
+/// Error returned when a [`Spayd`] cannot be built from key/value data
+#[derive(Debug, PartialEq)]
+pub enum SpaydMapError {
+    /// Mandatory key is missing
+    MissingKey(SpaydKey),
+
+    /// Key is not recognized and does not start with `X-`
+    UnknownKey(String),
+
+    /// Field value failed validation
+    InvalidValue(SpaydError),
+}
+
+impl fmt::Display for SpaydMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydMapError::MissingKey(key) => write!(f, "missing mandatory key {}", key),
+            SpaydMapError::UnknownKey(key) => write!(f, "unknown key {}", key),
+            SpaydMapError::InvalidValue(source) => source.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SpaydMapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpaydMapError::InvalidValue(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for Spayd {
+    type Error = SpaydMapError;
+
+    /// Build from SPAYD field names and values
+    ///
+    /// Keys are matched case-insensitively. Keys starting with `X-` are kept as extras,
+    /// sorted by key.
+    ///
+    /// Keys are read in sorted order, as from a [`BTreeMap`], so of keys differing only in case
+    /// (`ACC` and `acc`) the last one sorted wins, and the error reported doesn't depend on the
+    /// hash order.
+    fn try_from(map: HashMap<String, String>) -> Result<Self, Self::Error> {
+        let mut pairs: Vec<_> = map.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        pairs.sort_unstable();
+
+        from_pairs(pairs.into_iter(), UnknownKeys::Reject)
+    }
+}
+
+impl TryFrom<&BTreeMap<String, String>> for Spayd {
+    type Error = SpaydMapError;
+
+    /// Build from SPAYD field names and values
+    ///
+    /// Keys are matched case-insensitively. Keys starting with `X-` are kept as extras,
+    /// sorted by key.
+    fn try_from(map: &BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        from_pairs(
+            map.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+            UnknownKeys::Reject,
+        )
+    }
+}
+
+impl From<&Spayd> for BTreeMap<String, String> {
+    fn from(spayd: &Spayd) -> Self {
+        spayd.to_map()
+    }
+}
+
+impl Spayd {
+    /// Set fields (including extras) keyed by SPAYD field names
+    ///
+    /// Payment and notify types are rendered to their codes (e.g. `IP`, `E`).
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.into_owned()))
+            .collect()
+    }
+}
+
+/// Handling of keys that are neither SPAYD fields nor start with `X-`
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum UnknownKeys {
+    /// Fail with [`SpaydMapError::UnknownKey`]
+    Reject,
+
+    /// Skip them
+    Ignore,
+
+    /// Keep them as extras
+    Keep,
+}
+
+/// Build from (key, value) pairs; if a key repeats, the last value wins
+pub(super) fn from_pairs<'m>(
+    pairs: impl Iterator<Item = (&'m str, &'m str)>,
+    unknown: UnknownKeys,
+) -> Result<Spayd, SpaydMapError> {
+    let mut account = None;
+    let mut bic = None;
+    let mut spayd = SpaydRef {
+        account: Cow::Borrowed(""),
+        bic: None,
+        alt_accounts: Vec::new(),
+        amount: None,
+        currency: None,
+        reference: None,
+        recipient: None,
+        date: None,
+        payment_type: None,
+        message: None,
+        notify: None,
+        notify_address: None,
+        extras: Vec::new(),
+        version: SpaydVersion::default(),
+        validation: ValidationOptions::default(),
+    };
+
+    for (key, value) in pairs {
+        let value = Cow::Borrowed(text::trim(value));
+
+        let parsed = match key.parse::<SpaydKey>() {
+            Ok(parsed) => parsed,
+            Err(_) if unknown == UnknownKeys::Ignore => continue,
+            Err(_) => return Err(SpaydMapError::UnknownKey(key.to_string())),
+        };
+
+        match parsed {
+            SpaydKey::Acc => {
+                let (iban, parsed_bic) = iban::split_bic(iban::normalize(value));
+                account = Some(iban);
+                bic = parsed_bic;
+            }
+            SpaydKey::AltAcc => spayd.alt_accounts = account::parse_list(&value),
+            SpaydKey::Am => spayd.amount = Some(value),
+            SpaydKey::Cc => spayd.currency = Some(currency::normalize(value)),
+            SpaydKey::Rf => spayd.reference = Some(value),
+            SpaydKey::Rn => spayd.recipient = Some(value),
+            SpaydKey::Dt => spayd.date = Some(value),
+            SpaydKey::Pt => spayd.payment_type = Some(payment_type::normalize(value)),
+            SpaydKey::Msg => spayd.message = Some(value),
+            SpaydKey::Nt => {
+                spayd.notify = Some(value.parse().map_err(SpaydMapError::InvalidValue)?)
+            }
+            SpaydKey::Nta => spayd.notify_address = Some(value),
+            SpaydKey::Custom(extra) if extra.starts_with("X-") || unknown == UnknownKeys::Keep => {
+                match spayd.extras.iter_mut().find(|(k, _)| *k == extra) {
+                    Some(existing) => existing.1 = value,
+                    None => spayd.extras.push((Cow::Owned(extra), value)),
+                }
+            }
+            SpaydKey::Custom(_) if unknown == UnknownKeys::Ignore => {}
+            SpaydKey::Custom(_) => return Err(SpaydMapError::UnknownKey(key.to_string())),
+        }
+    }
+
+    spayd.account = account.ok_or(SpaydMapError::MissingKey(SpaydKey::Acc))?;
+    spayd.bic = bic;
+    spayd.extras.sort_by(|a, b| a.0.cmp(&b.0));
+
+    spayd.validate().map_err(SpaydMapError::InvalidValue)?;
+
+    Ok(spayd.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn try_from_hash_map_works() {
+        let spayd = Spayd::try_from(map(&[
+            ("ACC", "CZ5508000000001234567899"),
+            ("AM", "239.50"),
+            ("CC", "CZK"),
+            ("PT", "IP"),
+            ("msg", "PAYMENT"),
+            ("NT", "E"),
+            ("NTA", "email@example.com"),
+            ("X-VS", "1234"),
+            ("X-KS", "0308"),
+        ]))
+        .unwrap();
+
+        assert!(matches!(spayd.payment_type, Some(PaymentType::Instant)));
+        assert_eq!(spayd.notify_type(), Some(NotifyType::Email));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*CC:CZK*PT:IP*MSG:PAYMENT*NT:E\
+             *NTA:email@example.com*X-KS:0308*X-VS:1234"
+        );
+    }
+
+    #[test]
+    fn try_from_btree_map_works() {
+        let map: BTreeMap<String, String> =
+            map(&[("ACC", "CZ5508000000001234567899"), ("AM", "239.50 ")])
+                .into_iter()
+                .collect();
+
+        let spayd = Spayd::try_from(&map).unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+    }
+
+    #[test]
+    fn to_map_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .payment_type(PaymentType::Instant)
+            .notify_email("email@example.com")
+            .extras(vec![("X-VS".to_string(), "1234".to_string())])
+            .build();
+
+        let map = BTreeMap::from(&spayd);
+
+        assert_eq!(
+            map,
+            BTreeMap::from_iter(
+                [
+                    ("ACC", "CZ5508000000001234567899"),
+                    ("AM", "239.50"),
+                    ("PT", "IP"),
+                    ("NT", "E"),
+                    ("NTA", "email@example.com"),
+                    ("X-VS", "1234"),
+                ]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+            )
+        );
+
+        let round_trip = Spayd::try_from(&map).unwrap().to_map();
+
+        assert_eq!(round_trip, map);
+    }
+
+    #[test]
+    fn try_from_missing_key_fails() {
+        let result = Spayd::try_from(map(&[("AM", "239.50")]));
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydMapError::MissingKey(SpaydKey::Acc)
+        );
+    }
+
+    #[test]
+    fn try_from_unknown_key_fails() {
+        let result = Spayd::try_from(map(&[
+            ("ACC", "CZ5508000000001234567899"),
+            ("AM", "239.50"),
+            ("VS", "1234"),
+        ]));
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydMapError::UnknownKey("VS".to_string())
+        );
+    }
+
+    #[test]
+    fn try_from_hash_map_is_deterministic() {
+        for _ in 0..20 {
+            let spayd = Spayd::try_from(map(&[
+                ("ACC", "CZ5508000000001234567899"),
+                ("acc", "CZ6508000000192000145399"),
+                ("AM", "239.50"),
+            ]))
+            .unwrap();
+
+            assert_eq!(spayd.account, "CZ6508000000192000145399");
+
+            let result = Spayd::try_from(map(&[
+                ("ACC", "CZ5508000000001234567899"),
+                ("VS", "1234"),
+                ("KS", "0308"),
+                ("SS", "1"),
+            ]));
+
+            assert_eq!(
+                result.unwrap_err(),
+                SpaydMapError::UnknownKey("KS".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn try_from_invalid_value_fails() {
+        let result = Spayd::try_from(map(&[
+            ("ACC", "CZ5508000000001234567899"),
+            ("AM", "239.50"),
+            ("NT", "X"),
+        ]));
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydMapError::InvalidValue(SpaydError::InvalidNotifyType("Value is not P or E"))
+        );
+
+        let result = Spayd::try_from(map(&[
+            ("ACC", "CZ5508000000001234567899"),
+            ("AM", "239.500"),
+        ]));
+
+        assert!(matches!(
+            result,
+            Err(SpaydMapError::InvalidValue(
+                SpaydError::AmountTooManyDecimals { found: 3 }
+            ))
+        ));
+    }
+}
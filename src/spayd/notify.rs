@@ -0,0 +1,526 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+/// Notification of the recipient: the type (`NT`) together with its address (`NTA`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// Email address
+    ///
+    /// A non-ASCII domain (`jan@příklad.cz`) is written out in its ASCII form
+    /// (`jan@xn--pklad-zsa96e.cz`); the local part must be ASCII.
+    Email(String),
+
+    /// Phone number
+    Phone(String),
+}
+
+impl Notification {
+    /// Type as written in the `NT` field
+    pub fn notify_type(&self) -> NotifyType {
+        match self {
+            Notification::Email(_) => NotifyType::Email,
+            Notification::Phone(_) => NotifyType::Phone,
+        }
+    }
+
+    /// Address as written in the `NTA` field
+    pub fn address(&self) -> &str {
+        match self {
+            Notification::Email(address) | Notification::Phone(address) => address,
+        }
+    }
+
+    /// Notification of the given type
+    pub(crate) fn new(notify: NotifyType, address: String) -> Self {
+        match notify {
+            NotifyType::Email => Notification::Email(address),
+            NotifyType::Phone => Notification::Phone(address),
+        }
+    }
+
+    /// Same notification with surrounding whitespace trimmed from the address
+    pub(crate) fn trimmed(self) -> Self {
+        let trimmed = text::trim(self.address());
+
+        if trimmed.len() == self.address().len() {
+            return self;
+        }
+
+        Notification::new(self.notify_type(), trimmed.to_string())
+    }
+}
+
+/// Compact form of a phone number (`00420 777-123-456` becomes `+420777123456`)
+///
+/// Spaces, dashes, dots and parentheses are removed and a leading `00` becomes `+`; other
+/// characters are kept, so validation still rejects them.
+pub(crate) fn normalize_phone(phone: Cow<'_, str>) -> Cow<'_, str> {
+    let separator = |c: char| matches!(c, ' ' | '-' | '.' | '(' | ')');
+
+    if !phone.contains(separator) && !phone.starts_with("00") {
+        return phone;
+    }
+
+    let compact: String = phone.chars().filter(|&c| !separator(c)).collect();
+
+    match compact.strip_prefix("00") {
+        Some(number) => Cow::Owned(format!("+{}", number)),
+        None => Cow::Owned(compact),
+    }
+}
+
+/// Address as written in `NTA`
+///
+/// A `mailto:` or `tel:` prefix is removed (ignoring case) and an internationalized email
+/// domain is converted to its ASCII form.
+pub(crate) fn written_address<'a>(
+    notify: Option<&NotifyType>,
+    address: Cow<'a, str>,
+) -> Cow<'a, str> {
+    let prefix = match notify {
+        Some(NotifyType::Email) => "mailto:",
+        Some(NotifyType::Phone) => "tel:",
+        None => return address,
+    };
+
+    let address = match address.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => match address {
+            Cow::Borrowed(address) => Cow::Borrowed(&address[prefix.len()..]),
+            Cow::Owned(address) => Cow::Owned(address[prefix.len()..].to_string()),
+        },
+        _ => address,
+    };
+
+    match notify {
+        Some(NotifyType::Email) => idn::email_to_ascii(address),
+        _ => address,
+    }
+}
+
+impl NotifyType {
+    /// Code as written in the `NT` field (`P` or `E`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyType::Phone => "P",
+            NotifyType::Email => "E",
+        }
+    }
+}
+
+/// Half of a notification set through the deprecated `notify` or `notify_address` builder
+/// setter, waiting for the other half
+#[doc(hidden)]
+pub struct PartialNotification<T>(T);
+
+// The builder's type parameter holds the state of each field, in declaration order; only the
+// state of `notification` matters to the setters below.
+macro_rules! builder_state {
+    ($notification:ty) => {
+        SpaydBuilder<(
+            Acc,
+            Bic,
+            Alt,
+            Am,
+            Cc,
+            Rf,
+            Rn,
+            Dt,
+            Pt,
+            Msg,
+            $notification,
+            Ext,
+            Ver,
+            Val,
+            Fmt,
+            Order,
+            Up,
+            Lossy,
+        )>
+    };
+}
+
+#[allow(clippy::type_complexity)]
+impl<Acc, Bic, Alt, Am, Cc, Rf, Rn, Dt, Pt, Msg, N, Ext, Ver, Val, Fmt, Order, Up, Lossy>
+    builder_state!(N)
+{
+    /// Builder with the state of `notification` changed by `f`
+    fn map_notification<T>(self, f: impl FnOnce(N) -> T) -> builder_state!(T) {
+        let (
+            acc,
+            bic,
+            alt,
+            am,
+            cc,
+            rf,
+            rn,
+            dt,
+            pt,
+            msg,
+            notification,
+            ext,
+            ver,
+            val,
+            fmt,
+            order,
+            up,
+            lossy,
+        ) = self.fields;
+
+        SpaydBuilder {
+            fields: (
+                acc,
+                bic,
+                alt,
+                am,
+                cc,
+                rf,
+                rn,
+                dt,
+                pt,
+                msg,
+                f(notification),
+                ext,
+                ver,
+                val,
+                fmt,
+                order,
+                up,
+                lossy,
+            ),
+            phantom: self.phantom,
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<Acc, Bic, Alt, Am, Cc, Rf, Rn, Dt, Pt, Msg, Ext, Ver, Val, Fmt, Order, Up, Lossy>
+    builder_state!(())
+{
+    /// Set email notification (`NT:E`) together with the address (`NTA`)
+    pub fn notify_email(
+        self,
+        address: impl Into<String>,
+    ) -> builder_state!((Option<Notification>,)) {
+        self.notification(Notification::Email(address.into()))
+    }
+
+    /// Set phone notification (`NT:P`) together with the phone number (`NTA`)
+    pub fn notify_phone(
+        self,
+        number: impl Into<String>,
+    ) -> builder_state!((Option<Notification>,)) {
+        self.notification(Notification::Phone(number.into()))
+    }
+
+    /// Like `notify_phone`, with the number normalized first (`+420 777 123 456` becomes
+    /// `+420777123456`)
+    pub fn notify_phone_lossy(
+        self,
+        number: impl Into<String>,
+    ) -> builder_state!((Option<Notification>,)) {
+        let number = normalize_phone(number.into().into()).into_owned();
+
+        self.notification(Notification::Phone(number))
+    }
+
+    /// Set notification type (`NT`); the address must follow with `notify_address`
+    #[deprecated(note = "use `notification`, `notify_email` or `notify_phone`")]
+    pub fn notify(self, notify: NotifyType) -> builder_state!((PartialNotification<NotifyType>,)) {
+        self.map_notification(|()| (PartialNotification(notify),))
+    }
+
+    /// Set notification address (`NTA`); the type must follow with `notify`
+    #[deprecated(note = "use `notification`, `notify_email` or `notify_phone`")]
+    pub fn notify_address(
+        self,
+        address: impl Into<String>,
+    ) -> builder_state!((PartialNotification<String>,)) {
+        self.map_notification(|()| (PartialNotification(address.into()),))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<Acc, Bic, Alt, Am, Cc, Rf, Rn, Dt, Pt, Msg, Ext, Ver, Val, Fmt, Order, Up, Lossy>
+    builder_state!((PartialNotification<NotifyType>,))
+{
+    /// Set notification address (`NTA`) for the type set with `notify`
+    #[deprecated(note = "use `notification`, `notify_email` or `notify_phone`")]
+    pub fn notify_address(
+        self,
+        address: impl Into<String>,
+    ) -> builder_state!((Option<Notification>,)) {
+        self.map_notification(|(PartialNotification(notify),)| {
+            (Some(Notification::new(notify, address.into()).trimmed()),)
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<Acc, Bic, Alt, Am, Cc, Rf, Rn, Dt, Pt, Msg, Ext, Ver, Val, Fmt, Order, Up, Lossy>
+    builder_state!((PartialNotification<String>,))
+{
+    /// Set notification type (`NT`) for the address set with `notify_address`
+    #[deprecated(note = "use `notification`, `notify_email` or `notify_phone`")]
+    pub fn notify(self, notify: NotifyType) -> builder_state!((Option<Notification>,)) {
+        self.map_notification(|(PartialNotification(address),)| {
+            (Some(Notification::new(notify, address).trimmed()),)
+        })
+    }
+}
+
+impl Spayd {
+    /// Notification (`NT` with `NTA`), if set
+    pub fn notification(&self) -> Option<&Notification> {
+        self.notification.as_ref()
+    }
+
+    /// Notification type (`NT`), if set
+    pub(crate) fn notify_type(&self) -> Option<NotifyType> {
+        self.notification.as_ref().map(Notification::notify_type)
+    }
+}
+
+impl FromStr for NotifyType {
+    type Err = SpaydError;
+
+    /// Parse `P` or `E`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "P" | "p" => Ok(NotifyType::Phone),
+            "E" | "e" => Ok(NotifyType::Email),
+            _ => Err(SpaydError::InvalidNotifyType("Value is not P or E")),
+        }
+    }
+}
+
+impl fmt::Display for NotifyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn notify_email_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_email(" email@example.com ")
+            .amount("239.50")
+            .build();
+
+        assert_eq!(
+            spayd.notification(),
+            Some(&Notification::Email("email@example.com".to_string()))
+        );
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*NT:E*NTA:email@example.com"
+        );
+
+        let spayd = Spayd::builder()
+            .notify_phone("+420123456789")
+            .account("CZ5508000000001234567899".to_string())
+            .build();
+
+        assert_eq!(spayd.notify_type(), Some(NotifyType::Phone));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:P*NTA:+420123456789"
+        );
+    }
+
+    #[test]
+    fn notification_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notification(Notification::Phone("+420123456789".to_string()))
+            .build();
+
+        assert_eq!(
+            spayd.notification(),
+            Some(&Notification::Phone("+420123456789".to_string()))
+        );
+        assert_eq!(spayd.get(SpaydKey::Nt).as_deref(), Some("P"));
+        assert_eq!(spayd.get(SpaydKey::Nta).as_deref(), Some("+420123456789"));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:P*NTA:+420123456789"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notification(Notification::Email("+420123456789".to_string()))
+            .build();
+
+        assert!(matches!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress(_))
+        ));
+
+        assert_eq!(
+            Notification::Email("a@b.cz".to_string()).address(),
+            "a@b.cz"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_setters_work() {
+        let expected = "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:email@example.com";
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify(NotifyType::Email)
+            .notify_address(" email@example.com ".to_string())
+            .build();
+
+        assert_eq!(spayd.spayd_string().unwrap(), expected);
+
+        let spayd = Spayd::builder()
+            .notify_address("email@example.com".to_string())
+            .account("CZ5508000000001234567899".to_string())
+            .notify(NotifyType::Email)
+            .build();
+
+        assert_eq!(spayd.spayd_string().unwrap(), expected);
+    }
+
+    #[test]
+    fn set_notification_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_phone("+420123456789")
+            .build();
+
+        spayd.set(SpaydKey::Nta, "+420987654321").unwrap();
+        assert_eq!(
+            spayd.notification(),
+            Some(&Notification::Phone("+420987654321".to_string()))
+        );
+
+        spayd.set(SpaydKey::Nta, "email@example.com").unwrap_err();
+
+        // the phone number doesn't fit an email notification, which waits for its address
+        spayd.set(SpaydKey::Nt, "E").unwrap();
+        assert_eq!(spayd.notification(), None);
+        assert_eq!(spayd.get(SpaydKey::Nt).as_deref(), Some("E"));
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Notify address was not provided"
+            ))
+        );
+
+        spayd.set(SpaydKey::Nta, "email@example.com").unwrap();
+        assert_eq!(
+            spayd.notification(),
+            Some(&Notification::Email("email@example.com".to_string()))
+        );
+
+        spayd.set(SpaydKey::Nt, "E").unwrap();
+        assert_eq!(
+            spayd.get(SpaydKey::Nta).as_deref(),
+            Some("email@example.com")
+        );
+
+        spayd.set(SpaydKey::Nt, "P").unwrap();
+        spayd.unset(SpaydKey::Nt).unwrap();
+        assert_eq!(spayd.notification(), None);
+        assert_eq!(spayd.get(SpaydKey::Nt), None);
+        assert_eq!(spayd.get(SpaydKey::Nta), None);
+    }
+
+    #[test]
+    fn notify_phone_lossy_works() {
+        let spayd = |number: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .notify_phone_lossy(number)
+                .build()
+                .spayd_string()
+        };
+
+        for (number, expected) in [
+            ("+420 777 123 456", "+420777123456"),
+            ("777-123-456", "777123456"),
+            ("00420777123456", "+420777123456"),
+            ("(+420) 777.123.456", "+420777123456"),
+        ] {
+            assert_eq!(
+                spayd(number).unwrap(),
+                format!("SPD*1.0*ACC:CZ5508000000001234567899*NT:P*NTA:{}", expected)
+            );
+        }
+
+        for number in ["777 ABC 456", "++420777123456", "+420+777123456"] {
+            assert_eq!(
+                spayd(number),
+                Err(SpaydError::InvalidNotifyAddress("Invalid phone number")),
+                "{}",
+                number
+            );
+        }
+    }
+
+    #[test]
+    fn address_prefix_is_stripped() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_email("MailTo:jan@example.com")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:jan@example.com"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_phone("tel:+420777123456")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:P*NTA:+420777123456"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_phone("mailto:+420777123456")
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidNotifyAddress("Invalid phone number"))
+        );
+    }
+
+    #[test]
+    fn notify_type_from_str_works() {
+        assert_eq!("P".parse(), Ok(NotifyType::Phone));
+        assert_eq!("e".parse(), Ok(NotifyType::Email));
+        assert_eq!(NotifyType::Phone.to_string(), "P");
+        assert_eq!(NotifyType::Email.to_string(), "E");
+
+        for s in ["", "X", "EMAIL", " E"] {
+            assert_eq!(
+                s.parse::<NotifyType>(),
+                Err(SpaydError::InvalidNotifyType("Value is not P or E"))
+            );
+        }
+
+        let spayd = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*NT:e*NTA:email@example.com")
+            .unwrap();
+
+        assert_eq!(spayd.notify_type(), Some(NotifyType::Email));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:E*NTA:email@example.com"
+        );
+    }
+}
@@ -0,0 +1,1418 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::encoding::decode;
+use super::*;
+
+/// Error returned when a SPAYD string cannot be parsed
+///
+/// Offsets are byte offsets into the parsed input.
+#[derive(Debug, PartialEq)]
+pub enum SpaydParseError {
+    /// Input does not start with the `SPD` header
+    MissingHeader,
+
+    /// Version following the header is not supported
+    UnsupportedVersion {
+        /// Version found in the input
+        version: String,
+        /// Offset of the version
+        offset: usize,
+    },
+
+    /// Field is not in `KEY:VALUE` format
+    MalformedPair {
+        /// Index of the field (the first field after the header has index 0)
+        index: usize,
+        /// Raw field content
+        raw: String,
+        /// Offset of the field
+        offset: usize,
+    },
+
+    /// Key is not recognized
+    UnknownKey {
+        /// Key found in the input
+        key: String,
+        /// Offset of the key
+        offset: usize,
+    },
+
+    /// Value contains an invalid percent-escape (e.g. `%G1` or a truncated `%2`)
+    InvalidEscape {
+        /// Offending sequence
+        sequence: String,
+        /// Offset of the sequence
+        offset: usize,
+    },
+
+    /// Key appears more than once (with [`DuplicateKeyPolicy::Reject`])
+    DuplicateKey {
+        /// Duplicated key
+        key: SpaydKey,
+        /// Offset of the first occurrence
+        first_offset: usize,
+        /// Offset of the repeated occurrence
+        offset: usize,
+    },
+
+    /// Mandatory field is missing
+    MissingField {
+        /// Key of the missing field
+        key: SpaydKey,
+    },
+
+    /// `CRC32` field doesn't match the other fields, so the payload was damaged or changed
+    CrcMismatch {
+        /// Checksum as written in the payload
+        expected: String,
+        /// Checksum of the other fields
+        computed: String,
+        /// Offset of the checksum
+        offset: usize,
+    },
+
+    /// Field value failed validation
+    InvalidValue {
+        /// Key of the invalid field
+        key: SpaydKey,
+        /// Offset of the value
+        offset: usize,
+        /// Validation error
+        source: SpaydError,
+    },
+}
+
+impl SpaydParseError {
+    /// Byte offset into the input the error relates to, if any
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            SpaydParseError::MissingHeader => Some(0),
+            SpaydParseError::UnsupportedVersion { offset, .. }
+            | SpaydParseError::MalformedPair { offset, .. }
+            | SpaydParseError::UnknownKey { offset, .. }
+            | SpaydParseError::InvalidEscape { offset, .. }
+            | SpaydParseError::DuplicateKey { offset, .. }
+            | SpaydParseError::CrcMismatch { offset, .. }
+            | SpaydParseError::InvalidValue { offset, .. } => Some(*offset),
+            SpaydParseError::MissingField { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for SpaydParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydParseError::MissingHeader => write!(f, "missing SPD header at offset 0"),
+            SpaydParseError::UnsupportedVersion { version, offset } => {
+                write!(f, "unsupported version {:?} at offset {}", version, offset)
+            }
+            SpaydParseError::MalformedPair { index, raw, offset } => write!(
+                f,
+                "malformed field #{} {:?} at offset {}: expected KEY:VALUE",
+                index, raw, offset
+            ),
+            SpaydParseError::UnknownKey { key, offset } => {
+                write!(f, "unknown key {} at offset {}", key, offset)
+            }
+            SpaydParseError::InvalidEscape { sequence, offset } => {
+                write!(f, "invalid escape {:?} at offset {}", sequence, offset)
+            }
+            SpaydParseError::DuplicateKey {
+                key,
+                first_offset,
+                offset,
+            } => write!(
+                f,
+                "duplicate key {} at offset {} (first occurrence at offset {})",
+                key, offset, first_offset
+            ),
+            SpaydParseError::MissingField { key } => write!(f, "missing mandatory field {}", key),
+            SpaydParseError::CrcMismatch {
+                expected,
+                computed,
+                offset,
+            } => write!(
+                f,
+                "checksum {} at offset {} does not match the payload, computed {}",
+                expected, offset, computed
+            ),
+            SpaydParseError::InvalidValue {
+                key,
+                offset,
+                source,
+            } => {
+                write!(f, "invalid value for {} at offset {}: ", key, offset)?;
+                source.fmt_message(f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpaydParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpaydParseError::InvalidValue { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Invalid optional field ignored by [`Spayd::parse_lenient`]
+#[derive(Debug, PartialEq)]
+pub struct SpaydWarning {
+    /// Key of the ignored field
+    pub key: SpaydKey,
+    /// Offset of the ignored value
+    pub offset: usize,
+    /// Reason the field was ignored
+    pub reason: SpaydError,
+}
+
+impl fmt::Display for SpaydWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ignored invalid {} at offset {}: ",
+            self.key, self.offset
+        )?;
+        self.reason.fmt_message(f)
+    }
+}
+
+/// What to do when a key appears more than once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Fail with [`SpaydParseError::DuplicateKey`]
+    #[default]
+    Reject,
+
+    /// Keep the first occurrence
+    FirstWins,
+
+    /// Keep the last occurrence
+    LastWins,
+}
+
+/// What to do when the `CRC32` field doesn't match the other fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrcMismatchPolicy {
+    /// Fail with [`SpaydParseError::CrcMismatch`]
+    #[default]
+    Reject,
+
+    /// Accept the payload; [`Spayd::parse_lenient_with`] reports the mismatch as a warning
+    Warn,
+}
+
+/// Parser options
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Reject keys not modelled by this crate instead of collecting them into extras
+    pub reject_unknown_keys: bool,
+
+    /// Require uppercase keys and header, as the specification mandates
+    ///
+    /// By default keys are matched case-insensitively and normalized to uppercase.
+    pub strict_keys: bool,
+
+    /// Handling of repeated keys (including unknown ones)
+    pub duplicate_keys: DuplicateKeyPolicy,
+
+    /// Accept version `1.1` in addition to `1.0`
+    pub allow_version_1_1: bool,
+
+    /// Handling of a `CRC32` field that doesn't match the other fields, which are sorted as
+    /// in [`Spayd::canonical_string`] to compute the checksum
+    pub crc_mismatch: CrcMismatchPolicy,
+
+    /// Options used to validate the parsed values (and kept for generating output)
+    pub validation: ValidationOptions,
+}
+
+impl Spayd {
+    /// Parse SPAYD string (alias for [`str::parse`])
+    pub fn parse(s: &str) -> Result<Spayd, SpaydParseError> {
+        s.parse()
+    }
+
+    /// Parse SPAYD string with custom options
+    ///
+    /// A leading BOM, surrounding whitespace and a single trailing `*` are ignored.
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Spayd, SpaydParseError> {
+        SpaydRef::parse_with(s, options).map(|spayd| spayd.to_owned())
+    }
+}
+
+impl<'a> SpaydRef<'a> {
+    /// Parse SPAYD string without copying the values
+    pub fn parse(s: &'a str) -> Result<SpaydRef<'a>, SpaydParseError> {
+        SpaydRef::parse_with(s, &ParseOptions::default())
+    }
+
+    /// Parse SPAYD string with custom options, without copying the values
+    ///
+    /// A leading BOM, surrounding whitespace and a single trailing `*` are ignored.
+    pub fn parse_with(s: &'a str, options: &ParseOptions) -> Result<SpaydRef<'a>, SpaydParseError> {
+        SpaydRef::parse_inner(s, options, Mode::FirstError)
+            .map(|(spayd, _)| spayd)
+            .map_err(|errors| {
+                errors
+                    .into_iter()
+                    .next()
+                    .expect("parsing failed with an error")
+            })
+    }
+
+    fn parse_inner(
+        s: &'a str,
+        options: &ParseOptions,
+        mode: Mode,
+    ) -> Result<(SpaydRef<'a>, Vec<SpaydWarning>), Vec<SpaydParseError>> {
+        let collect_all = mode != Mode::FirstError;
+        let payload = s.trim_start_matches(|c: char| c == '\u{FEFF}' || c.is_whitespace());
+        let start = s.len() - payload.len();
+        let payload = payload.trim_end();
+        let payload = payload.strip_suffix('*').unwrap_or(payload);
+        let end = start + payload.len();
+
+        let mut tokens = tokenize(payload, start);
+
+        match tokens.next() {
+            Some((_, "SPD")) => {}
+            Some((_, header)) if !options.strict_keys && header.eq_ignore_ascii_case("SPD") => {}
+            _ => return Err(vec![SpaydParseError::MissingHeader]),
+        }
+
+        let version = match tokens.next() {
+            Some((_, "1.0")) => SpaydVersion::V1_0,
+            Some((_, "1.1")) if options.allow_version_1_1 => SpaydVersion::V1_1,
+            // a field right after the header means the version is missing
+            Some((offset, version)) if !version.contains(':') => {
+                return Err(vec![SpaydParseError::UnsupportedVersion {
+                    version: version.to_string(),
+                    offset,
+                }])
+            }
+            Some((offset, _)) => {
+                return Err(vec![SpaydParseError::UnsupportedVersion {
+                    version: String::new(),
+                    offset,
+                }])
+            }
+            None => {
+                return Err(vec![SpaydParseError::UnsupportedVersion {
+                    version: String::new(),
+                    offset: end,
+                }])
+            }
+        };
+
+        let mut account = None;
+        let mut bic = None;
+        let mut alt_accounts = Vec::new();
+        let mut amount = None;
+        let mut currency = None;
+        let mut reference = None;
+        let mut recipient = None;
+        let mut date = None;
+        let mut payment_type = None;
+        let mut message = None;
+        let mut notify = None;
+        let mut notify_address = None;
+        let mut extras = Vec::new();
+
+        let mut errors = Vec::new();
+
+        // record an error and skip the field, or stop at the first error
+        macro_rules! fail {
+            ($error:expr) => {{
+                errors.push($error);
+
+                if !collect_all {
+                    return Err(errors);
+                }
+
+                continue;
+            }};
+        }
+
+        // key offsets, used to detect duplicates
+        let mut seen: Vec<(Cow<'a, str>, usize)> = Vec::with_capacity(10);
+
+        // value offsets, used to locate validation errors
+        let mut offsets: Vec<(SpaydKey, usize)> = Vec::with_capacity(10);
+
+        // fields as written and the `CRC32` field with its offset, used to verify the checksum
+        let mut written: Vec<(SpaydKey, Cow<'a, str>)> = Vec::with_capacity(10);
+        let mut crc = None;
+
+        for (index, (offset, token)) in tokens.enumerate() {
+            // values may contain ':' (e.g. `MSG:PO: 123`), so only the first one separates the key
+            let Some((key, value)) = token.split_once(':') else {
+                fail!(SpaydParseError::MalformedPair {
+                    index,
+                    raw: token.to_string(),
+                    offset,
+                })
+            };
+            let value_offset = offset + key.len() + 1;
+
+            if options.strict_keys && key.bytes().any(|b| b.is_ascii_lowercase()) {
+                fail!(SpaydParseError::UnknownKey {
+                    key: key.to_string(),
+                    offset,
+                });
+            }
+
+            let key = if key.bytes().any(|b| b.is_ascii_lowercase()) {
+                Cow::Owned(key.to_ascii_uppercase())
+            } else {
+                Cow::Borrowed(key)
+            };
+
+            if key == "CRC32" {
+                crc = Some((value, value_offset));
+            } else {
+                let written_key =
+                    SpaydKey::from_name(&key).unwrap_or_else(|| SpaydKey::Custom(key.to_string()));
+                written.push((written_key, Cow::Borrowed(value)));
+            }
+
+            if let Some(&(_, first_offset)) = seen.iter().find(|(k, _)| *k == key) {
+                match options.duplicate_keys {
+                    DuplicateKeyPolicy::Reject => fail!(SpaydParseError::DuplicateKey {
+                        key: SpaydKey::from_name(&key)
+                            .unwrap_or_else(|| SpaydKey::Custom(key.into_owned())),
+                        first_offset,
+                        offset,
+                    }),
+                    DuplicateKeyPolicy::FirstWins => continue,
+                    DuplicateKeyPolicy::LastWins => {}
+                }
+            } else {
+                seen.push((key.clone(), offset));
+            }
+
+            // verified below and not kept, it would go stale once the payment is changed
+            if key == "CRC32" {
+                continue;
+            }
+
+            let value = match decode(value) {
+                Ok(value) => value,
+                Err(e) => fail!(SpaydParseError::InvalidEscape {
+                    sequence: e.sequence,
+                    offset: value_offset + e.offset,
+                }),
+            };
+
+            let known = SpaydKey::from_name(&key);
+
+            match known {
+                Some(SpaydKey::Acc) => {
+                    let (iban, parsed_bic) = iban::split_bic(iban::normalize(value));
+                    account = Some(iban);
+                    bic = parsed_bic;
+                }
+                Some(SpaydKey::AltAcc) => {
+                    alt_accounts = account::parse_list(&value);
+                }
+                Some(SpaydKey::Am) => {
+                    amount = Some(value);
+                }
+                Some(SpaydKey::Cc) => {
+                    currency = Some(currency::normalize(value));
+                }
+                Some(SpaydKey::Rf) => {
+                    reference = Some(value);
+                }
+                Some(SpaydKey::Rn) => {
+                    recipient = Some(text::compose(value));
+                }
+                Some(SpaydKey::Dt) => {
+                    date = Some(value);
+                }
+                Some(SpaydKey::Pt) => {
+                    payment_type = Some(payment_type::normalize(value));
+                }
+                Some(SpaydKey::Msg) => {
+                    message = Some(text::compose(value));
+                }
+                Some(SpaydKey::Nt) => {
+                    notify = Some(match value.parse() {
+                        Ok(notify) => notify,
+                        Err(source) => fail!(SpaydParseError::InvalidValue {
+                            key: SpaydKey::Nt,
+                            offset: value_offset,
+                            source,
+                        }),
+                    });
+                }
+                Some(SpaydKey::Nta) => {
+                    notify_address = Some(value);
+                }
+                Some(SpaydKey::Custom(_)) => unreachable!("custom keys are not modelled"),
+                None if options.reject_unknown_keys => fail!(SpaydParseError::UnknownKey {
+                    key: key.into_owned(),
+                    offset,
+                }),
+                None => {
+                    match extras.iter_mut().find(|(k, _)| *k == key) {
+                        Some(extra) => extra.1 = value,
+                        None => extras.push((key, value)),
+                    }
+                    continue;
+                }
+            }
+
+            if let Some(key) = known {
+                offsets.push((key, value_offset));
+            }
+        }
+
+        let mut warnings = Vec::new();
+
+        if let Some((expected, offset)) = crc {
+            let computed = crc::checksum(version, written);
+
+            if !expected.eq_ignore_ascii_case(&computed) {
+                match options.crc_mismatch {
+                    CrcMismatchPolicy::Reject => {
+                        errors.push(SpaydParseError::CrcMismatch {
+                            expected: expected.to_string(),
+                            computed,
+                            offset,
+                        });
+
+                        if !collect_all {
+                            return Err(errors);
+                        }
+                    }
+                    CrcMismatchPolicy::Warn => warnings.push(SpaydWarning {
+                        key: SpaydKey::Custom("CRC32".to_string()),
+                        offset,
+                        reason: SpaydError::CrcMismatch {
+                            expected: expected.to_string(),
+                            computed,
+                        },
+                    }),
+                }
+            }
+        }
+
+        let mut missing = Vec::new();
+
+        if account.is_none() {
+            errors.push(SpaydParseError::MissingField { key: SpaydKey::Acc });
+            missing.push(SpaydKey::Acc);
+
+            if !collect_all {
+                return Err(errors);
+            }
+        }
+
+        let mut spayd = SpaydRef {
+            account: account.unwrap_or_default(),
+            bic,
+            alt_accounts,
+            amount,
+            currency,
+            reference,
+            recipient,
+            date,
+            payment_type,
+            message,
+            notify,
+            notify_address,
+            extras,
+            version,
+            validation: options.validation.clone(),
+        };
+
+        let invalid = |source: SpaydError| {
+            let key = source.key();
+            let offset = offsets
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == key)
+                .map_or(end, |(_, offset)| *offset);
+
+            SpaydParseError::InvalidValue {
+                key,
+                offset,
+                source,
+            }
+        };
+
+        if collect_all {
+            errors.extend(
+                spayd
+                    .fields()
+                    .errors()
+                    .into_iter()
+                    .filter(|source| !missing.contains(&source.key()))
+                    .map(invalid),
+            );
+        } else if let Err(source) = spayd.validate() {
+            errors.push(invalid(source));
+        }
+
+        if mode == Mode::Lenient {
+            errors = spayd.clear_invalid(errors, &mut warnings);
+
+            // the checks spanning the payload see the fields as kept, and dropping one doesn't
+            // fix every error they find (e.g. `X-PER` without `DT`)
+            if errors.is_empty() {
+                let payload_errors = spayd.payload_errors().into_iter().map(invalid).collect();
+
+                errors = spayd.clear_invalid(payload_errors, &mut warnings);
+                errors.extend(spayd.payload_errors().into_iter().map(invalid));
+            }
+
+            warnings.sort_by_key(|warning| warning.offset);
+        } else if collect_all && missing.is_empty() {
+            errors.extend(spayd.payload_errors().into_iter().map(invalid));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok((spayd, warnings))
+    }
+
+    /// Unset the optional fields with invalid values, recording a warning for each, and return
+    /// the other errors
+    ///
+    /// Errors of the whole payload or of unmodelled fields (e.g. the payload length) aren't
+    /// fixed by unsetting a field.
+    fn clear_invalid(
+        &mut self,
+        errors: Vec<SpaydParseError>,
+        warnings: &mut Vec<SpaydWarning>,
+    ) -> Vec<SpaydParseError> {
+        let (ignored, fatal): (Vec<_>, Vec<_>) = errors.into_iter().partition(|error| {
+            matches!(error, SpaydParseError::InvalidValue { key, .. }
+                if !matches!(key, SpaydKey::Acc | SpaydKey::Am | SpaydKey::Custom(_)))
+        });
+
+        for error in ignored {
+            if let SpaydParseError::InvalidValue {
+                key,
+                offset,
+                source,
+            } = error
+            {
+                self.clear(&key);
+                warnings.push(SpaydWarning {
+                    key,
+                    offset,
+                    reason: source,
+                });
+            }
+        }
+
+        fatal
+    }
+
+    /// Unset an optional field
+    fn clear(&mut self, key: &SpaydKey) {
+        match key {
+            SpaydKey::Acc | SpaydKey::Am | SpaydKey::Custom(_) => {
+                unreachable!("only modelled fields other than ACC and AM are cleared")
+            }
+            SpaydKey::AltAcc => self.alt_accounts.clear(),
+            SpaydKey::Cc => self.currency = None,
+            SpaydKey::Rf => self.reference = None,
+            SpaydKey::Rn => self.recipient = None,
+            SpaydKey::Dt => self.date = None,
+            SpaydKey::Pt => self.payment_type = None,
+            SpaydKey::Msg => self.message = None,
+            SpaydKey::Nt | SpaydKey::Nta => {
+                self.notify = None;
+                self.notify_address = None;
+            }
+        }
+    }
+}
+
+impl Spayd {
+    /// Parse SPAYD string, reporting every problem instead of stopping at the first one
+    ///
+    /// Malformed fields are skipped and scanning continues, so e.g. an invalid amount
+    /// and an invalid date produce two errors. A missing or unsupported header still
+    /// stops parsing immediately.
+    pub fn parse_all_errors(s: &str) -> Result<Spayd, Vec<SpaydParseError>> {
+        SpaydRef::parse_inner(s, &ParseOptions::default(), Mode::AllErrors)
+            .map(|(spayd, _)| spayd.to_owned())
+    }
+
+    /// Parse SPAYD string, ignoring invalid optional fields
+    ///
+    /// Optional fields that fail validation (e.g. `MSG` with a forbidden character or a
+    /// malformed `DT`) are left unset and reported as warnings. Problems with the account
+    /// (`ACC`) or amount (`AM`), problems of the whole payload (e.g. its length) and malformed
+    /// input still fail.
+    pub fn parse_lenient(s: &str) -> Result<(Spayd, Vec<SpaydWarning>), SpaydParseError> {
+        Spayd::parse_lenient_with(s, &ParseOptions::default())
+    }
+
+    /// Like [`Spayd::parse_lenient`], with custom options
+    pub fn parse_lenient_with(
+        s: &str,
+        options: &ParseOptions,
+    ) -> Result<(Spayd, Vec<SpaydWarning>), SpaydParseError> {
+        SpaydRef::parse_inner(s, options, Mode::Lenient)
+            .map(|(spayd, warnings)| (spayd.to_owned(), warnings))
+            .map_err(|errors| {
+                errors
+                    .into_iter()
+                    .next()
+                    .expect("parsing failed with an error")
+            })
+    }
+}
+
+/// How parsing reacts to errors
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// Stop at the first error
+    FirstError,
+
+    /// Collect all errors
+    AllErrors,
+
+    /// Collect all errors, then ignore invalid optional fields with a warning
+    Lenient,
+}
+
+impl FromStr for Spayd {
+    type Err = SpaydParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Spayd::parse_with(s, &ParseOptions::default())
+    }
+}
+
+/// Split input on `*`, yielding each token with its byte offset (counted from `start`)
+fn tokenize(s: &str, start: usize) -> impl Iterator<Item = (usize, &str)> {
+    s.split('*').scan(start, |offset, token| {
+        let start = *offset;
+        *offset += token.len() + 1;
+
+        Some((start, token))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn parse_basic_works() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50");
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50".to_string()
+        );
+    }
+
+    #[test]
+    fn parse_round_trip_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .currency("CZK".to_string())
+            .reference("123121".to_string())
+            .recipient("MISTR1/+.% PO".to_string())
+            .date("20230810".to_string())
+            .payment_type(PaymentType::Instant)
+            .message("PAYMENT".to_string())
+            .notify_email("email@example.com")
+            .build();
+
+        let original = spayd.spayd_string().unwrap();
+        let parsed: Spayd = original.parse().unwrap();
+
+        assert!(matches!(parsed.payment_type, Some(PaymentType::Instant)));
+        assert_eq!(parsed.notify_type(), Some(NotifyType::Email));
+        assert_eq!(parsed.spayd_string(), Ok(original));
+    }
+
+    #[test]
+    fn parse_bom_and_whitespace_works() {
+        let result = Spayd::parse("\u{FEFF}SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50\r\n");
+
+        assert_eq!(
+            result.unwrap().spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50".to_string()
+        );
+
+        let result = Spayd::parse("  SPD*1.0*ACC:CZ5508000000001234567899*AM:5*  ");
+
+        assert_eq!(
+            result.unwrap().spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:5".to_string()
+        );
+    }
+
+    #[test]
+    fn parse_offsets_account_for_trimmed_prefix() {
+        let result = Spayd::parse("\u{FEFF} SPD*1.0*ACC:CZ5508000000001234567899*AM239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::MalformedPair {
+                index: 1,
+                raw: "AM239.50".to_string(),
+                offset: 41
+            }
+        );
+    }
+
+    #[test]
+    fn parse_empty_trailing_pair_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:5**");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::MalformedPair {
+                index: 2,
+                raw: "".to_string(),
+                offset: 42
+            }
+        );
+    }
+
+    #[test]
+    fn parse_missing_header_fails() {
+        let result = Spayd::parse("ACC:CZ5508000000001234567899*AM:239.50");
+
+        assert_eq!(result.unwrap_err(), SpaydParseError::MissingHeader);
+    }
+
+    #[test]
+    fn parse_unsupported_version_fails() {
+        let result = Spayd::parse("SPD*2.0*ACC:CZ5508000000001234567899*AM:239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnsupportedVersion {
+                version: "2.0".to_string(),
+                offset: 4
+            }
+        );
+    }
+
+    #[test]
+    fn parse_missing_version_fails() {
+        let result = Spayd::parse("SPD");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnsupportedVersion {
+                version: "".to_string(),
+                offset: 3
+            }
+        );
+
+        let result = Spayd::parse("SPD*");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnsupportedVersion {
+                version: "".to_string(),
+                offset: 3
+            }
+        );
+
+        let result = Spayd::parse("SPD*ACC:CZ5508000000001234567899*AM:239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnsupportedVersion {
+                version: "".to_string(),
+                offset: 4
+            }
+        );
+    }
+
+    #[test]
+    fn parse_version_works() {
+        let spayd = Spayd::parse("spd*1.0*ACC:CZ5508000000001234567899*AM:239.50").unwrap();
+
+        assert_eq!(spayd.version(), SpaydVersion::V1_0);
+
+        let input = "SPD*1.1*ACC:CZ5508000000001234567899*AM:239.50";
+
+        assert_eq!(
+            Spayd::parse(input).unwrap_err(),
+            SpaydParseError::UnsupportedVersion {
+                version: "1.1".to_string(),
+                offset: 4
+            }
+        );
+
+        let options = ParseOptions {
+            allow_version_1_1: true,
+            ..Default::default()
+        };
+        let spayd = Spayd::parse_with(input, &options).unwrap();
+
+        assert_eq!(spayd.version(), SpaydVersion::V1_1);
+        assert_eq!(spayd.spayd_string().unwrap(), input);
+    }
+
+    #[test]
+    fn parse_field_without_colon_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::MalformedPair {
+                index: 1,
+                raw: "AM239.50".to_string(),
+                offset: 37
+            }
+        );
+    }
+
+    #[test]
+    fn parse_splits_on_first_colon_only() {
+        let input = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RN:MISTR1/+.% PO:";
+        let spayd = Spayd::parse(input).unwrap();
+
+        assert_eq!(spayd.recipient.as_deref(), Some("MISTR1/+.% PO:"));
+        assert_eq!(spayd.spayd_string().unwrap(), input);
+
+        let spayd =
+            Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PO: FAKTURA 123")
+                .unwrap();
+
+        assert_eq!(spayd.message.as_deref(), Some("PO: FAKTURA 123"));
+    }
+
+    #[test]
+    fn parse_generate_symmetry_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .recipient("MISTR1/+.% PO:".to_string())
+            .build();
+
+        let original = spayd.spayd_string().unwrap();
+        let parsed = Spayd::parse(&original).unwrap();
+
+        assert_eq!(parsed.recipient, spayd.recipient);
+        assert_eq!(parsed.spayd_string(), Ok(original));
+    }
+
+    #[test]
+    fn parse_missing_account_fails() {
+        let result = Spayd::parse("SPD*1.0*AM:239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::MissingField { key: SpaydKey::Acc }
+        );
+    }
+
+    #[test]
+    fn parse_without_amount_works() {
+        let input = "SPD*1.0*ACC:CZ5508000000001234567899*MSG:DAR";
+        let spayd = Spayd::parse(input).unwrap();
+
+        assert_eq!(spayd.amount, None);
+        assert_eq!(spayd.spayd_string().unwrap(), input);
+    }
+
+    #[test]
+    fn parse_unknown_keys_are_preserved() {
+        let input =
+            "SPD*1.0*ACC:CZ5508000000001234567899*X-VS:1234*AM:239.50*X-ID:AB*CRC32:D5A3D82B";
+        let spayd = Spayd::parse(input).unwrap();
+
+        assert_eq!(spayd.extra("X-VS"), Some("1234"));
+        assert_eq!(spayd.extra("CRC32"), None);
+        assert_eq!(spayd.extra("X-KS"), None);
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*X-VS:1234*X-ID:AB"
+        );
+    }
+
+    #[test]
+    fn parse_strict_unknown_key_fails() {
+        let options = ParseOptions {
+            reject_unknown_keys: true,
+            ..Default::default()
+        };
+        let result = Spayd::parse_with(
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*X-VS:1234",
+            &options,
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnknownKey {
+                key: "X-VS".to_string(),
+                offset: 47
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lowercase_keys_works() {
+        let spayd = Spayd::parse(
+            "spd*1.0*acc:CZ5508000000001234567899*Am:239.50*nta:email@example.com*NT:E*x-vs:12",
+        )
+        .unwrap();
+
+        assert_eq!(
+            spayd.get(SpaydKey::Nta).as_deref(),
+            Some("email@example.com")
+        );
+        assert_eq!(spayd.extra("X-VS"), Some("12"));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*NT:E*NTA:email@example.com*X-VS:12"
+        );
+    }
+
+    #[test]
+    fn parse_strict_keys_lowercase_fails() {
+        let options = ParseOptions {
+            strict_keys: true,
+            ..Default::default()
+        };
+
+        let result = Spayd::parse_with("SPD*1.0*ACC:CZ5508000000001234567899*am:239.50", &options);
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::UnknownKey {
+                key: "am".to_string(),
+                offset: 37
+            }
+        );
+
+        let result = Spayd::parse_with("spd*1.0*ACC:CZ5508000000001234567899*AM:239.50", &options);
+
+        assert_eq!(result.unwrap_err(), SpaydParseError::MissingHeader);
+    }
+
+    #[test]
+    fn parse_duplicate_key_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*MSG:A*AM:239.50*MSG:B");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::DuplicateKey {
+                key: SpaydKey::Msg,
+                first_offset: 37,
+                offset: 53
+            }
+        );
+
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*X-VS:1*x-vs:2");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::DuplicateKey {
+                key: SpaydKey::Custom("X-VS".to_string()),
+                first_offset: 47,
+                offset: 54
+            }
+        );
+    }
+
+    #[test]
+    fn parse_duplicate_key_policy_works() {
+        let input = "SPD*1.0*ACC:CZ5508000000001234567899*MSG:A*X-VS:1*AM:239.50*MSG:B*X-VS:2";
+
+        let first = Spayd::parse_with(
+            input,
+            &ParseOptions {
+                duplicate_keys: DuplicateKeyPolicy::FirstWins,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(first.message.as_deref(), Some("A"));
+        assert_eq!(first.extras(), [("X-VS".to_string(), "1".to_string())]);
+
+        let last = Spayd::parse_with(
+            input,
+            &ParseOptions {
+                duplicate_keys: DuplicateKeyPolicy::LastWins,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(last.message.as_deref(), Some("B"));
+        assert_eq!(last.extras(), [("X-VS".to_string(), "2".to_string())]);
+    }
+
+    #[test]
+    fn parse_normalizes_account() {
+        let spayd = Spayd::parse("SPD*1.0*ACC:cz55 0800 0000 0012 3456 7899*AM:239.50").unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+    }
+
+    #[test]
+    fn parse_bic_works() {
+        let input = "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZPP*AM:239.50";
+        let spayd = SpaydRef::parse(input).unwrap();
+
+        assert_eq!(spayd.account(), "CZ5855000000001265098001");
+        assert_eq!(spayd.bic(), Some("RZBCCZPP"));
+        assert_eq!(spayd.to_owned().spayd_string().unwrap(), input);
+    }
+
+    #[test]
+    fn parse_invalid_bic_fails() {
+        for input in [
+            "SPD*1.0*ACC:CZ5855000000001265098001+RZBCCZP*AM:239.50",
+            "SPD*1.0*ACC:+RZBCCZPP*AM:239.50",
+        ] {
+            assert!(matches!(
+                Spayd::parse(input),
+                Err(SpaydParseError::InvalidValue {
+                    key: SpaydKey::Acc,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_decodes_values() {
+        let spayd =
+            Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA%20ZA%20ZBOZI")
+                .unwrap();
+
+        assert_eq!(spayd.message.as_deref(), Some("PLATBA ZA ZBOZI"));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA ZA ZBOZI"
+        );
+    }
+
+    #[test]
+    fn parse_invalid_escape_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:A%G1");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::InvalidEscape {
+                sequence: "%G1".to_string(),
+                offset: 52
+            }
+        );
+
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:A%2");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::InvalidEscape {
+                sequence: "%2".to_string(),
+                offset: 52
+            }
+        );
+    }
+
+    #[test]
+    fn asterisk_is_encoded_on_output() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .message("A*B".to_string())
+            .extras(vec![("X-ID".to_string(), "1*2".to_string())])
+            .build();
+
+        let result = spayd.spayd_string_unchecked();
+
+        assert_eq!(
+            result,
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:A%2AB*X-ID:1%2A2"
+        );
+
+        let parsed = Spayd::parse_with(
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*X-ID:1%2A2",
+            &ParseOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(parsed.extra("X-ID"), Some("1*2"));
+    }
+
+    #[test]
+    fn parse_all_errors_works() {
+        let result = Spayd::parse_all_errors(
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.500*DT:2023-08-10*MSG:PAYMENT",
+        );
+
+        assert_eq!(
+            result.unwrap_err(),
+            vec![
+                SpaydParseError::InvalidValue {
+                    key: SpaydKey::Am,
+                    offset: 40,
+                    source: SpaydError::AmountTooManyDecimals { found: 3 }
+                },
+                SpaydParseError::InvalidValue {
+                    key: SpaydKey::Dt,
+                    offset: 51,
+                    source: SpaydError::InvalidDate("Date is not in YYYYMMDD format")
+                },
+            ]
+        );
+
+        let spayd =
+            Spayd::parse_all_errors("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50").unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+    }
+
+    #[test]
+    fn parse_all_errors_continues_after_malformed_pair() {
+        let result = Spayd::parse_all_errors("SPD*1.0*ACC:CZ5508000000001234567899*AM239.50*NT:X");
+
+        assert_eq!(
+            result.unwrap_err(),
+            vec![
+                SpaydParseError::MalformedPair {
+                    index: 1,
+                    raw: "AM239.50".to_string(),
+                    offset: 37
+                },
+                SpaydParseError::InvalidValue {
+                    key: SpaydKey::Nt,
+                    offset: 49,
+                    source: SpaydError::InvalidNotifyType("Value is not P or E")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_lenient_works() {
+        let (spayd, warnings) = Spayd::parse_lenient(
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*DT:2023-08-10*RN:Novak*RF:12",
+        )
+        .unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RF:12"
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                SpaydWarning {
+                    key: SpaydKey::Dt,
+                    offset: 50,
+                    reason: SpaydError::InvalidDate("Date is not in YYYYMMDD format")
+                },
+                SpaydWarning {
+                    key: SpaydKey::Rn,
+                    offset: 64,
+                    reason: SpaydError::InvalidRecipient("Value contains forbidden character(s)")
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "ignored invalid DT at offset 50: Date is not in YYYYMMDD format"
+        );
+    }
+
+    #[test]
+    fn parse_lenient_drops_dependent_fields() {
+        let (spayd, warnings) = Spayd::parse_lenient(
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*NT:X*NTA:email@example.com",
+        )
+        .unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+        assert_eq!(
+            warnings.iter().map(|w| w.key.clone()).collect::<Vec<_>>(),
+            [SpaydKey::Nt, SpaydKey::Nta]
+        );
+    }
+
+    #[test]
+    fn parse_runs_payload_checks() {
+        let long = format!(
+            "SPD*1.0*ACC:CZ5508000000001234567899*X-NOTE:{}",
+            "A".repeat(700)
+        );
+        let options = |level, profile| ParseOptions {
+            validation: ValidationOptions {
+                level,
+                profile,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let airbank = options(ValidationLevel::Standard, Some(ValidationProfile::AIRBANK));
+        let strict = options(ValidationLevel::Strict, None);
+
+        assert_eq!(
+            Spayd::parse(&long).unwrap_err(),
+            SpaydParseError::InvalidValue {
+                key: SpaydKey::Custom(String::new()),
+                offset: long.len(),
+                source: SpaydError::PayloadTooLong {
+                    len: long.len(),
+                    max: 600
+                }
+            }
+        );
+        assert!(matches!(
+            Spayd::parse_all_errors(&long).unwrap_err().as_slice(),
+            [SpaydParseError::InvalidValue {
+                source: SpaydError::PayloadTooLong { .. },
+                ..
+            }]
+        ));
+        assert!(Spayd::parse_lenient(&long).is_err());
+
+        let reference = "SPD*1.0*ACC:CZ5508000000001234567899*RF:12345678901";
+
+        assert!(matches!(
+            Spayd::parse_with(reference, &airbank),
+            Err(SpaydParseError::InvalidValue {
+                key: SpaydKey::Rf,
+                offset: 40,
+                source: SpaydError::RejectedByProfile { .. }
+            })
+        ));
+
+        let foreign = "SPD*1.0*ACC:DE89370400440532013000";
+
+        assert_eq!(
+            Spayd::parse_with(foreign, &strict).unwrap_err(),
+            SpaydParseError::InvalidValue {
+                key: SpaydKey::Cc,
+                offset: foreign.len(),
+                source: SpaydError::Inconsistent(Inconsistency::ForeignAccountWithoutCurrency)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_lenient_runs_payload_checks() {
+        let options = ParseOptions {
+            validation: ValidationOptions {
+                profile: Some(ValidationProfile::AIRBANK),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (spayd, warnings) = Spayd::parse_lenient_with(
+            "SPD*1.0*ACC:CZ5508000000001234567899*RF:12345678901",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(spayd.reference, None);
+        assert_eq!(
+            warnings.iter().map(|w| w.key.clone()).collect::<Vec<_>>(),
+            [SpaydKey::Rf]
+        );
+    }
+
+    #[test]
+    fn parse_lenient_mandatory_fields_fail() {
+        let result =
+            Spayd::parse_lenient("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.500*DT:2023");
+
+        assert!(matches!(
+            result,
+            Err(SpaydParseError::InvalidValue {
+                key: SpaydKey::Am,
+                ..
+            })
+        ));
+
+        let result = Spayd::parse_lenient("SPD*1.0*AM:239.50");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::MissingField { key: SpaydKey::Acc }
+        );
+    }
+
+    #[test]
+    fn parse_invalid_notify_type_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*NT:X");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::InvalidValue {
+                key: SpaydKey::Nt,
+                offset: 50,
+                source: SpaydError::InvalidNotifyType("Value is not P or E")
+            }
+        );
+    }
+
+    #[test]
+    fn parse_notify_half_fails() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*NT:E");
+
+        assert!(matches!(
+            result,
+            Err(SpaydParseError::InvalidValue {
+                source: SpaydError::InvalidNotifyAddress("Notify address was not provided"),
+                ..
+            })
+        ));
+
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*NTA:email@example.com");
+
+        assert!(matches!(
+            result,
+            Err(SpaydParseError::InvalidValue {
+                source: SpaydError::InvalidNotifyAddress("Notify type was not provided"),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_validates_values() {
+        let result = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*AM:239.500");
+        let err = result.unwrap_err();
+
+        assert_eq!(
+            err,
+            SpaydParseError::InvalidValue {
+                key: SpaydKey::Am,
+                offset: 40,
+                source: SpaydError::AmountTooManyDecimals { found: 3 }
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid value for AM at offset 40: Maximum number of decimal places is 2, got 3"
+        );
+    }
+}
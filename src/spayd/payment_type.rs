@@ -0,0 +1,139 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use super::*;
+
+impl PaymentType {
+    /// Payment type codes with a known meaning
+    pub const KNOWN_CODES: [&'static str; 1] = ["IP"];
+
+    /// Code as written in the `PT` field (e.g. `IP`)
+    pub fn as_str(&self) -> &str {
+        match self {
+            PaymentType::Instant => "IP",
+            PaymentType::Other(code) => code,
+        }
+    }
+
+    /// Check that the code is in [`PaymentType::KNOWN_CODES`]
+    pub fn is_known(&self) -> bool {
+        PaymentType::KNOWN_CODES.contains(&self.as_str())
+    }
+
+    /// Payment type from a raw code, without validation
+    pub(crate) fn from_code(code: &str) -> Self {
+        if code.eq_ignore_ascii_case("IP") {
+            PaymentType::Instant
+        } else {
+            PaymentType::Other(code.to_string())
+        }
+    }
+}
+
+/// Uppercase a payment type code (`ip` becomes `IP`)
+pub(crate) fn normalize(code: Cow<'_, str>) -> Cow<'_, str> {
+    currency::normalize(code)
+}
+
+impl FromStr for PaymentType {
+    type Err = SpaydError;
+
+    /// Parse a code of up to 3 letters and digits, ignoring case (`ip` becomes
+    /// [`PaymentType::Instant`])
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(SpaydError::InvalidPaymentType("Value is empty"));
+        }
+
+        check_len(SpaydKey::Pt, s, limits::MAX_PAYMENT_TYPE_CHARS)?;
+
+        if !s.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(SpaydError::InvalidPaymentType(
+                "Value contains forbidden character(s)",
+            ));
+        }
+
+        Ok(PaymentType::from_code(&s.to_ascii_uppercase()))
+    }
+}
+
+impl fmt::Display for PaymentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn payment_type_from_str_works() {
+        assert_eq!("IP".parse(), Ok(PaymentType::Instant));
+        assert_eq!("ip".parse(), Ok(PaymentType::Instant));
+        assert_eq!("dd".parse(), Ok(PaymentType::Other("DD".to_string())));
+        assert_eq!(PaymentType::Instant.to_string(), "IP");
+        assert_eq!(PaymentType::Other("DD".to_string()).to_string(), "DD");
+        assert!(PaymentType::Instant.is_known());
+        assert!(!PaymentType::Other("DD".to_string()).is_known());
+    }
+
+    #[test]
+    fn invalid_payment_type_from_str_fails() {
+        assert_eq!(
+            "".parse::<PaymentType>(),
+            Err(SpaydError::InvalidPaymentType("Value is empty"))
+        );
+        assert_eq!(
+            "ABCD".parse::<PaymentType>(),
+            Err(SpaydError::TooLong {
+                key: SpaydKey::Pt,
+                len: 4,
+                max: limits::MAX_PAYMENT_TYPE_CHARS
+            })
+        );
+
+        assert_eq!(
+            "A*".parse::<PaymentType>(),
+            Err(SpaydError::InvalidPaymentType(
+                "Value contains forbidden character(s)"
+            ))
+        );
+    }
+
+    #[test]
+    fn payment_type_validation_works() {
+        let spayd = |payment_type: PaymentType, reject_unknown_payment_types| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .payment_type(payment_type)
+                .validation(ValidationOptions {
+                    reject_unknown_payment_types,
+                    ..Default::default()
+                })
+                .build()
+                .spayd_string()
+        };
+
+        assert!(spayd(PaymentType::Instant, true).is_ok());
+        assert!(spayd(PaymentType::Other("DD".to_string()), false).is_ok());
+        assert_eq!(
+            spayd(PaymentType::Other("DD".to_string()), true),
+            Err(SpaydError::InvalidPaymentType("Unknown payment type"))
+        );
+
+        for code in ["ip", "D D", "D.D"] {
+            assert_eq!(
+                spayd(PaymentType::Other(code.to_string()), false),
+                Err(SpaydError::InvalidPaymentType(
+                    "Value contains forbidden character(s)"
+                ))
+            );
+        }
+
+        let spayd = Spayd::parse("SPD*1.0*ACC:CZ5508000000001234567899*PT:ip").unwrap();
+
+        assert_eq!(spayd.payment_type, Some(PaymentType::Instant));
+    }
+}
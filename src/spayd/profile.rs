@@ -0,0 +1,319 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use super::*;
+
+/// Limits and supported features of a bank's SPAYD implementation, on top of the specification
+///
+/// Select one with [`ValidationOptions::profile`]. Limits the bank enforces fail validation,
+/// while fields the bank accepts but shortens or ignores are reported by
+/// [`Spayd::profile_warnings`].
+///
+/// The presets reflect what the banks were observed to do; check them against the bank's
+/// current documentation, or construct your own profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationProfile {
+    /// Name of the bank
+    pub name: Cow<'static, str>,
+
+    /// Characters of `MSG` the bank keeps; longer messages are accepted but truncated
+    pub message_len: Option<usize>,
+
+    /// Characters of `RN` the bank keeps; longer names are accepted but truncated
+    pub recipient_len: Option<usize>,
+
+    /// Maximum length of `RF` the bank accepts; longer references fail validation
+    pub max_reference_len: Option<usize>,
+
+    /// The bank processes instant payments (`PT:IP`)
+    pub instant_payments: bool,
+
+    /// The bank sends notifications (`NT` and `NTA`)
+    pub notifications: bool,
+
+    /// Accept only IBANs from these countries, in addition to
+    /// [`ValidationOptions::allowed_countries`]
+    pub allowed_countries: Option<Cow<'static, [[u8; 2]]>>,
+
+    /// Currency written as `CC` when unset, and checked against
+    /// [`ValidationOptions::allowed_currencies`] in place of
+    /// [`ValidationOptions::default_currency`]
+    pub default_currency: Option<CurrencyCode>,
+}
+
+impl ValidationProfile {
+    /// Česká spořitelna, which truncates `MSG` at 35 characters
+    pub const CESKA_SPORITELNA: ValidationProfile = ValidationProfile {
+        name: Cow::Borrowed("Česká spořitelna"),
+        message_len: Some(35),
+        ..ValidationProfile::SPEC
+    };
+
+    /// Fio banka, which ignores `PT:IP`
+    pub const FIO: ValidationProfile = ValidationProfile {
+        name: Cow::Borrowed("Fio banka"),
+        instant_payments: false,
+        ..ValidationProfile::SPEC
+    };
+
+    /// Air Bank, which rejects `RF` longer than 10 digits
+    pub const AIRBANK: ValidationProfile = ValidationProfile {
+        name: Cow::Borrowed("Air Bank"),
+        max_reference_len: Some(10),
+        ..ValidationProfile::SPEC
+    };
+
+    /// Payments for Slovak customers: Slovak or Czech IBANs, in `EUR` unless set otherwise
+    pub const SLOVAKIA: ValidationProfile = ValidationProfile {
+        name: Cow::Borrowed("Slovensko"),
+        allowed_countries: Some(Cow::Borrowed(&[*b"SK", *b"CZ"])),
+        default_currency: Some(CurrencyCode::EUR),
+        ..ValidationProfile::SPEC
+    };
+
+    /// Bank supporting the whole specification, to base custom profiles on
+    pub const SPEC: ValidationProfile = ValidationProfile {
+        name: Cow::Borrowed("SPAYD"),
+        message_len: None,
+        recipient_len: None,
+        max_reference_len: None,
+        instant_payments: true,
+        notifications: true,
+        allowed_countries: None,
+        default_currency: None,
+    };
+
+    /// Check the limits the bank enforces
+    pub(crate) fn validate(&self, spayd: &Spayd) -> Result<(), SpaydError> {
+        if let (Some(reference), Some(max)) = (&spayd.reference, self.max_reference_len) {
+            let len = reference.chars().count();
+
+            if len > max {
+                return Err(SpaydError::RejectedByProfile {
+                    key: SpaydKey::Rf,
+                    len,
+                    max,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn warnings(&self, spayd: &Spayd) -> Vec<ProfileWarning> {
+        let mut warnings = Vec::new();
+
+        for (key, value, max) in [
+            (SpaydKey::Rn, &spayd.recipient, self.recipient_len),
+            (SpaydKey::Msg, &spayd.message, self.message_len),
+        ] {
+            if let (Some(value), Some(max)) = (value, max) {
+                let len = value.chars().count();
+
+                if len > max {
+                    warnings.push(ProfileWarning::Truncated { key, len, max });
+                }
+            }
+        }
+
+        if !self.instant_payments && spayd.payment_type == Some(PaymentType::Instant) {
+            warnings.push(ProfileWarning::Ignored { key: SpaydKey::Pt });
+        }
+
+        if !self.notifications {
+            for key in [SpaydKey::Nt, SpaydKey::Nta] {
+                if spayd.get(key.clone()).is_some() {
+                    warnings.push(ProfileWarning::Ignored { key });
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Field the bank accepts, but doesn't process as given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileWarning {
+    /// The bank keeps only the first `max` characters
+    Truncated {
+        /// Key of the field
+        key: SpaydKey,
+        /// Length of the value in characters
+        len: usize,
+        /// Characters the bank keeps
+        max: usize,
+    },
+
+    /// The bank ignores the field
+    Ignored {
+        /// Key of the field
+        key: SpaydKey,
+    },
+}
+
+impl fmt::Display for ProfileWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileWarning::Truncated { key, len, max } => write!(
+                f,
+                "{} has {} characters and will be truncated to {}",
+                key, len, max
+            ),
+            ProfileWarning::Ignored { key } => write!(f, "{} will be ignored", key),
+        }
+    }
+}
+
+impl Spayd {
+    /// Fields the bank of [`ValidationOptions::profile`] accepts but shortens or ignores
+    pub fn profile_warnings(&self) -> Vec<ProfileWarning> {
+        match &self.validation.profile {
+            Some(profile) => profile.warnings(self),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    fn options(profile: ValidationProfile) -> ValidationOptions {
+        ValidationOptions {
+            profile: Some(profile),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_warnings_work() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message("PLATBA ZA FAKTURU 2024001 OBJEDNAVKA 1234")
+            .payment_type(PaymentType::Instant)
+            .validation(options(ValidationProfile::CESKA_SPORITELNA))
+            .build();
+
+        assert!(spayd.spayd_string().is_ok());
+        assert_eq!(
+            spayd.profile_warnings(),
+            [ProfileWarning::Truncated {
+                key: SpaydKey::Msg,
+                len: 41,
+                max: 35
+            }]
+        );
+        assert_eq!(
+            spayd.profile_warnings()[0].to_string(),
+            "MSG has 41 characters and will be truncated to 35"
+        );
+
+        let mut spayd = spayd;
+        spayd.validation = options(ValidationProfile::FIO);
+
+        assert_eq!(
+            spayd.profile_warnings(),
+            [ProfileWarning::Ignored { key: SpaydKey::Pt }]
+        );
+
+        spayd.validation = Default::default();
+        assert_eq!(spayd.profile_warnings(), []);
+    }
+
+    #[test]
+    fn profile_limit_fails() {
+        let spayd = |reference: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .reference(reference)
+                .validation(options(ValidationProfile::AIRBANK))
+                .build()
+        };
+
+        assert!(spayd("1234567890").spayd_string().is_ok());
+
+        let result = spayd("12345678901").spayd_string();
+
+        assert_eq!(
+            result,
+            Err(SpaydError::RejectedByProfile {
+                key: SpaydKey::Rf,
+                len: 11,
+                max: 10
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "invalid RF: Exceeded the bank's maximum length of 10 characters, got 11"
+        );
+    }
+
+    #[test]
+    fn custom_profile_works() {
+        let profile = ValidationProfile {
+            name: "My bank".into(),
+            recipient_len: Some(20),
+            notifications: false,
+            ..ValidationProfile::SPEC
+        };
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("NOVACEK SYNOVE VYROBA HRACEK".to_string())
+            .notify_email("jan@example.com")
+            .validation(options(profile))
+            .build();
+
+        assert_eq!(
+            spayd.profile_warnings(),
+            [
+                ProfileWarning::Truncated {
+                    key: SpaydKey::Rn,
+                    len: 28,
+                    max: 20
+                },
+                ProfileWarning::Ignored { key: SpaydKey::Nt },
+                ProfileWarning::Ignored { key: SpaydKey::Nta },
+            ]
+        );
+    }
+
+    #[test]
+    fn slovakia_profile_works() {
+        let spayd = |account: &str| {
+            Spayd::builder()
+                .account(account.to_string())
+                .amount("12.50")
+                .message("PLATBA")
+                .validation(options(ValidationProfile::SLOVAKIA))
+                .build()
+        };
+
+        assert_eq!(
+            spayd("SK3112000000198742637541").spayd_string().unwrap(),
+            "SPD*1.0*ACC:SK3112000000198742637541*AM:12.50*CC:EUR*MSG:PLATBA"
+        );
+        assert!(spayd("CZ5508000000001234567899").spayd_string().is_ok());
+        assert_eq!(
+            spayd("AT611904300234573201").spayd_string(),
+            Err(SpaydError::CountryNotAllowed { country: *b"AT" })
+        );
+
+        let mut spayd = spayd("SK3112000000198742637541");
+        spayd.set(SpaydKey::Cc, "CZK").unwrap();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:SK3112000000198742637541*AM:12.50*CC:CZK*MSG:PLATBA"
+        );
+
+        spayd.unset(SpaydKey::Cc).unwrap();
+        spayd.validation.allowed_currencies = Some(vec![CurrencyCode::EUR]);
+
+        assert!(spayd.spayd_string().is_ok());
+        assert_eq!(
+            spayd.canonical_string().unwrap(),
+            "SPD*1.0*ACC:SK3112000000198742637541*AM:12.50*CC:EUR*MSG:PLATBA"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use image::GrayImage;
+
+use super::*;
+
+/// Error returned when a SPAYD payment cannot be decoded from a QR code image
+#[derive(Debug)]
+pub enum SpaydDecodeError {
+    /// Image data could not be decoded (unsupported or corrupted PNG/JPEG)
+    InvalidImage(image::ImageError),
+
+    /// No QR code was found in the image
+    NoQrCode,
+
+    /// QR code was found, but could not be read
+    UnreadableQrCode(rqrr::DeQRError),
+
+    /// QR code was read, but does not contain a SPAYD payload
+    NotSpayd {
+        /// Content of the QR code
+        content: String,
+    },
+
+    /// QR code contains a SPAYD payload which failed to parse or validate
+    InvalidPayload(SpaydParseError),
+}
+
+impl fmt::Display for SpaydDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpaydDecodeError::InvalidImage(e) => write!(f, "invalid image: {}", e),
+            SpaydDecodeError::NoQrCode => write!(f, "no QR code found in the image"),
+            SpaydDecodeError::UnreadableQrCode(e) => write!(f, "unreadable QR code: {}", e),
+            SpaydDecodeError::NotSpayd { content } => {
+                write!(f, "QR code does not contain a SPAYD payload: {:?}", content)
+            }
+            SpaydDecodeError::InvalidPayload(e) => write!(f, "invalid SPAYD payload: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SpaydDecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SpaydDecodeError::InvalidImage(e) => Some(e),
+            SpaydDecodeError::UnreadableQrCode(e) => Some(e),
+            SpaydDecodeError::InvalidPayload(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Spayd {
+    /// Decode payment from a QR code image
+    ///
+    /// If the image contains more QR codes, the first one carrying a SPAYD payload is used.
+    pub fn from_qr_image(img: &GrayImage) -> Result<Spayd, SpaydDecodeError> {
+        let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+            img.width() as usize,
+            img.height() as usize,
+            |x, y| img.get_pixel(x as u32, y as u32).0[0],
+        );
+
+        let grids = prepared.detect_grids();
+
+        if grids.is_empty() {
+            return Err(SpaydDecodeError::NoQrCode);
+        }
+
+        let mut error = None;
+
+        for grid in grids {
+            match grid.decode() {
+                Ok((_, content)) if is_spayd(&content) => {
+                    return Spayd::parse(&content).map_err(SpaydDecodeError::InvalidPayload)
+                }
+                Ok((_, content)) => {
+                    error.get_or_insert(SpaydDecodeError::NotSpayd { content });
+                }
+                Err(e) => {
+                    error.get_or_insert(SpaydDecodeError::UnreadableQrCode(e));
+                }
+            }
+        }
+
+        Err(error.unwrap_or(SpaydDecodeError::NoQrCode))
+    }
+
+    /// Decode payment from PNG or JPEG image data containing a QR code
+    pub fn from_qr_bytes(bytes: &[u8]) -> Result<Spayd, SpaydDecodeError> {
+        let img = image::load_from_memory(bytes).map_err(SpaydDecodeError::InvalidImage)?;
+
+        Spayd::from_qr_image(&img.to_luma8())
+    }
+}
+
+fn is_spayd(content: &str) -> bool {
+    content
+        .trim_start_matches(|c: char| c == '\u{FEFF}' || c.is_whitespace())
+        .get(..4)
+        .is_some_and(|header| header.eq_ignore_ascii_case("SPD*"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+    use image::{GrayImage, Luma};
+
+    #[test]
+    fn from_qr_image_blank_fails() {
+        let img = GrayImage::from_pixel(100, 100, Luma([255]));
+
+        assert!(matches!(
+            Spayd::from_qr_image(&img),
+            Err(SpaydDecodeError::NoQrCode)
+        ));
+    }
+
+    #[test]
+    fn from_qr_bytes_invalid_image_fails() {
+        assert!(matches!(
+            Spayd::from_qr_bytes(b"not an image"),
+            Err(SpaydDecodeError::InvalidImage(_))
+        ));
+    }
+
+    #[cfg(feature = "qrcode")]
+    fn render(content: &str) -> GrayImage {
+        let code = qrcode::QrCode::new(content).unwrap();
+        let width = code.width() as u32;
+        let colors = code.to_colors();
+        let scale = 8;
+        let quiet_zone = 4;
+        let size = (width + 2 * quiet_zone) * scale;
+
+        GrayImage::from_fn(size, size, |x, y| {
+            let (x, y) = (x / scale, y / scale);
+
+            if x < quiet_zone
+                || y < quiet_zone
+                || x >= width + quiet_zone
+                || y >= width + quiet_zone
+            {
+                return Luma([255]);
+            }
+
+            match colors[((y - quiet_zone) * width + x - quiet_zone) as usize] {
+                qrcode::Color::Dark => Luma([0]),
+                qrcode::Color::Light => Luma([255]),
+            }
+        })
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn from_qr_image_works() {
+        let payload = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PAYMENT";
+        let spayd = Spayd::from_qr_image(&render(payload)).unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), payload);
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn from_qr_bytes_works() {
+        let payload = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50";
+        let mut png = std::io::Cursor::new(Vec::new());
+
+        render(payload)
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+
+        let spayd = Spayd::from_qr_bytes(png.get_ref()).unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), payload);
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn from_qr_image_not_spayd_fails() {
+        let result = Spayd::from_qr_image(&render("https://example.com"));
+
+        assert!(matches!(
+            result,
+            Err(SpaydDecodeError::NotSpayd { content }) if content == "https://example.com"
+        ));
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn from_qr_image_invalid_payload_fails() {
+        let result = Spayd::from_qr_image(&render("SPD*1.0*AM:239.50"));
+
+        assert!(matches!(
+            result,
+            Err(SpaydDecodeError::InvalidPayload(
+                SpaydParseError::MissingField { key: SpaydKey::Acc }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use super::encoding::{decode_form_component, encode_form_component};
+use super::map::{from_pairs, UnknownKeys};
+use super::*;
+
+/// Query string parsing options
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Keep unrecognized parameters as extras instead of ignoring them
+    ///
+    /// Parameters starting with `X-` are always kept.
+    pub keep_unknown: bool,
+}
+
+impl Spayd {
+    /// Generate a form-urlencoded query string (e.g. `ACC=CZ..&AM=239.50&MSG=PLATBA+ZA+ZBOZI`)
+    ///
+    /// Fields are written in the same order as in [`Spayd::spayd_string`].
+    pub fn to_query_string(&self) -> Result<String, SpaydError> {
+        self.validate()?;
+
+        let params: Vec<String> = self
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    encode_form_component(key.as_str()),
+                    encode_form_component(&value)
+                )
+            })
+            .collect();
+
+        Ok(params.join("&"))
+    }
+
+    /// Parse payment from a URL query string (e.g. `?acc=CZ..&am=239.50&msg=PAYMENT`)
+    ///
+    /// Parameters are form-urlencoded (`+` is a space) and matched case-insensitively.
+    /// Unrecognized parameters are ignored.
+    pub fn from_query_str(query: &str) -> Result<Spayd, SpaydMapError> {
+        Spayd::from_query_str_with(query, &QueryOptions::default())
+    }
+
+    /// Parse payment from a URL query string with custom options
+    ///
+    /// If a parameter repeats, the last value wins. Parameters with an empty value are
+    /// treated as not set.
+    pub fn from_query_str_with(
+        query: &str,
+        options: &QueryOptions,
+    ) -> Result<Spayd, SpaydMapError> {
+        let query = query.strip_prefix('?').unwrap_or(query);
+
+        let params: Vec<(String, String)> = query
+            .split('&')
+            .filter_map(|param| {
+                let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                let value = decode_form_component(value);
+
+                (!key.is_empty() && !value.is_empty())
+                    .then(|| (decode_form_component(key).into_owned(), value.into_owned()))
+            })
+            .collect();
+
+        let unknown = if options.keep_unknown {
+            UnknownKeys::Keep
+        } else {
+            UnknownKeys::Ignore
+        };
+
+        from_pairs(
+            params.iter().map(|(k, v)| (k.as_str(), v.as_str())),
+            unknown,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn from_query_str_works() {
+        let spayd = Spayd::from_query_str(
+            "?acc=CZ5508000000001234567899&am=239.50&MSG=PLATBA+ZA+ZBOZI+10%25&utm_source=x",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.message.as_deref(), Some("PLATBA ZA ZBOZI 10%"));
+        assert!(spayd.extras().is_empty());
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA ZA ZBOZI 10%"
+        );
+    }
+
+    #[test]
+    fn from_query_str_keep_unknown_works() {
+        let options = QueryOptions { keep_unknown: true };
+        let spayd = Spayd::from_query_str_with(
+            "acc=CZ5508000000001234567899&am=239.50&vs=1234&x-ks=0308",
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            spayd.extras(),
+            [
+                ("VS".to_string(), "1234".to_string()),
+                ("X-KS".to_string(), "0308".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn from_query_str_repeated_and_empty_params_work() {
+        let spayd = Spayd::from_query_str(
+            "acc=CZ5508000000001234567899&am=1&am=239.50&msg=&cc&x-vs=1&X-VS=2",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.amount.as_deref(), Some("239.50"));
+        assert_eq!(spayd.message, None);
+        assert_eq!(spayd.currency, None);
+        assert_eq!(spayd.extra("X-VS"), Some("2"));
+    }
+
+    #[test]
+    fn to_query_string_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .currency("CZK".to_string())
+            .recipient("MISTR1/+.% PO:".to_string())
+            .payment_type(PaymentType::Instant)
+            .message("PLATBA ZA ZBOZI".to_string())
+            .extras(vec![("X-VS".to_string(), "1234".to_string())])
+            .build();
+
+        let query = spayd.to_query_string().unwrap();
+
+        assert_eq!(
+            query,
+            "ACC=CZ5508000000001234567899&AM=239.50&CC=CZK&RN=MISTR1%2F%2B.%25+PO%3A&PT=IP\
+             &MSG=PLATBA+ZA+ZBOZI&X-VS=1234"
+        );
+
+        let parsed = Spayd::from_query_str(&query).unwrap();
+
+        assert_eq!(parsed.spayd_string(), spayd.spayd_string());
+    }
+
+    #[test]
+    fn to_query_string_validates() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.500".to_string())
+            .build();
+
+        assert!(matches!(
+            spayd.to_query_string(),
+            Err(SpaydError::AmountTooManyDecimals { found: 3 })
+        ));
+    }
+
+    #[test]
+    fn from_query_str_validates() {
+        assert_eq!(
+            Spayd::from_query_str("acc=&am=239.50").unwrap_err(),
+            SpaydMapError::MissingKey(SpaydKey::Acc)
+        );
+        assert!(matches!(
+            Spayd::from_query_str("acc=CZ5508000000001234567899&am=239.500"),
+            Err(SpaydMapError::InvalidValue(
+                SpaydError::AmountTooManyDecimals { found: 3 }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use super::*;
+
+/// Reference for the `RF` field, e.g. from a number
+///
+/// Pass it to the builder's `reference` setter like a [`String`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference(String);
+
+impl Reference {
+    /// Reference from a number with at most 16 digits
+    pub fn from_num(reference: u64) -> Result<Self, SpaydError> {
+        let reference = reference.to_string();
+
+        if reference.len() > limits::MAX_REFERENCE_DIGITS {
+            return Err(SpaydError::TooLong {
+                key: SpaydKey::Rf,
+                len: reference.len(),
+                max: limits::MAX_REFERENCE_DIGITS,
+            });
+        }
+
+        Ok(Reference(reference))
+    }
+
+    /// Reference as written in the `RF` field
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Reference> for String {
+    fn from(reference: Reference) -> Self {
+        reference.0
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Spayd {
+    /// Reference (`RF`) as a number, if set and numeric
+    ///
+    /// Leading zeros are lost (`007` becomes `7`); the stored reference keeps them.
+    pub fn reference_num(&self) -> Option<u64> {
+        let reference = self.reference.as_deref()?;
+
+        if !reference.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        reference.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn from_num_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .reference(Reference::from_num(1234567890).unwrap())
+            .build();
+
+        assert_eq!(spayd.reference_num(), Some(1234567890));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*RF:1234567890"
+        );
+        assert_eq!(
+            Reference::from_num(9_999_999_999_999_999).unwrap().as_str(),
+            "9999999999999999"
+        );
+        assert_eq!(
+            Reference::from_num(10_000_000_000_000_000),
+            Err(SpaydError::TooLong {
+                key: SpaydKey::Rf,
+                len: 17,
+                max: limits::MAX_REFERENCE_DIGITS
+            })
+        );
+    }
+
+    #[test]
+    fn reference_keeps_leading_zeros() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .reference("007")
+            .build();
+
+        assert_eq!(spayd.reference_num(), Some(7));
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*RF:007"
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .reference("+7")
+            .build();
+
+        assert_eq!(spayd.reference_num(), None);
+    }
+}
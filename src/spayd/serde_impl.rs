@@ -0,0 +1,115 @@
+use std::fmt;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::*;
+
+impl Serialize for Spayd {
+    /// Serialize as the validated SPAYD string
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let spayd = self.spayd_string().map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_str(&spayd)
+    }
+}
+
+impl<'de> Deserialize<'de> for Spayd {
+    /// Deserialize from a SPAYD string
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(SpaydVisitor)
+    }
+}
+
+struct SpaydVisitor;
+
+impl Visitor<'_> for SpaydVisitor {
+    type Value = Spayd;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a SPAYD string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Spayd, E> {
+        Spayd::parse(v).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Invoice {
+        number: u32,
+        payment: Spayd,
+    }
+
+    #[test]
+    fn serde_round_trip_works() {
+        let invoice = Invoice {
+            number: 42,
+            payment: Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .amount("239.50".to_string())
+                .message("PLATBA ZA ZBOZI".to_string())
+                .build(),
+        };
+
+        let json = serde_json::to_string(&invoice).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"number":42,"payment":"SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA ZA ZBOZI"}"#
+        );
+
+        let parsed: Invoice = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.number, 42);
+        assert_eq!(
+            parsed.payment.spayd_string(),
+            invoice.payment.spayd_string()
+        );
+    }
+
+    #[test]
+    fn serde_round_trip_keeps_percent_signs() {
+        for value in ["SLEVA 10%20", "%2A", "%FF"] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .message(value)
+                .build();
+
+            let json = serde_json::to_string(&spayd).unwrap();
+            let parsed: Spayd = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(parsed.message.as_deref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn serialize_invalid_fails() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.500".to_string())
+            .build();
+
+        assert!(serde_json::to_string(&spayd).is_err());
+    }
+
+    #[test]
+    fn deserialize_invalid_fails() {
+        let result: Result<Spayd, _> =
+            serde_json::from_str(r#""SPD*1.0*ACC:CZ5508000000001234567899*AM:239.500""#);
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid value for AM at offset 40"));
+
+        let result: Result<Spayd, _> = serde_json::from_str("42");
+
+        assert!(result.is_err());
+    }
+}
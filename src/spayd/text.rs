@@ -0,0 +1,1001 @@
+use std::collections::BTreeMap;
+
+use super::*;
+
+/// Czech and Slovak letters with diacritics and their ASCII base letters
+const DIACRITICS: [(char, char); 20] = [
+    ('á', 'A'),
+    ('ä', 'A'),
+    ('č', 'C'),
+    ('ď', 'D'),
+    ('é', 'E'),
+    ('ě', 'E'),
+    ('í', 'I'),
+    ('ĺ', 'L'),
+    ('ľ', 'L'),
+    ('ň', 'N'),
+    ('ó', 'O'),
+    ('ô', 'O'),
+    ('ŕ', 'R'),
+    ('ř', 'R'),
+    ('š', 'S'),
+    ('ť', 'T'),
+    ('ú', 'U'),
+    ('ů', 'U'),
+    ('ý', 'Y'),
+    ('ž', 'Z'),
+];
+
+/// Czech and Slovak letters with diacritics, as a base letter with a combining mark (NFD)
+const COMPOSITIONS: [(char, char, char); 20] = [
+    ('a', '\u{301}', 'á'),
+    ('a', '\u{308}', 'ä'),
+    ('c', '\u{30C}', 'č'),
+    ('d', '\u{30C}', 'ď'),
+    ('e', '\u{301}', 'é'),
+    ('e', '\u{30C}', 'ě'),
+    ('i', '\u{301}', 'í'),
+    ('l', '\u{301}', 'ĺ'),
+    ('l', '\u{30C}', 'ľ'),
+    ('n', '\u{30C}', 'ň'),
+    ('o', '\u{301}', 'ó'),
+    ('o', '\u{302}', 'ô'),
+    ('r', '\u{301}', 'ŕ'),
+    ('r', '\u{30C}', 'ř'),
+    ('s', '\u{30C}', 'š'),
+    ('t', '\u{30C}', 'ť'),
+    ('u', '\u{301}', 'ú'),
+    ('u', '\u{30A}', 'ů'),
+    ('y', '\u{301}', 'ý'),
+    ('z', '\u{30C}', 'ž'),
+];
+
+/// Mapping of characters to replacements used to convert text to the allowed character set
+///
+/// The default table covers Czech and Slovak letters with diacritics. Characters without a
+/// mapping are replaced with the placeholder (a space by default) or dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransliterationTable {
+    map: BTreeMap<char, String>,
+    placeholder: Option<char>,
+}
+
+impl TransliterationTable {
+    /// Table without any mappings, replacing unsupported characters with a space
+    pub fn empty() -> Self {
+        TransliterationTable {
+            map: BTreeMap::new(),
+            placeholder: Some(' '),
+        }
+    }
+
+    /// Add a mapping, e.g. `ß` to `SS`; lowercase letters in the replacement are uppercased
+    ///
+    /// The mapping applies to both cases of a letter unless the other case is mapped too.
+    pub fn with(mut self, from: char, to: impl Into<String>) -> Self {
+        self.map.insert(from, to.into());
+        self
+    }
+
+    /// Character used for unsupported characters, or `None` to drop them
+    pub fn placeholder(mut self, placeholder: Option<char>) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Convert text to the character set allowed by the specification
+    ///
+    /// Letters are uppercased, mapped characters are replaced, other unsupported characters
+    /// (e.g. `&`) are replaced with the placeholder and repeated spaces are collapsed.
+    /// Decomposed (NFD) Czech and Slovak letters are composed first, so `r` + caron is mapped
+    /// as `ř`; other combining diacritical marks are dropped.
+    pub fn transliterate(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+
+        for c in compose(value.into()).chars() {
+            if COMBINING_MARKS.contains(&c) {
+                continue;
+            } else if let Some(c) = allowed(c) {
+                push(&mut result, c);
+                continue;
+            }
+
+            let replacement = self
+                .map
+                .get(&c)
+                .or_else(|| c.to_lowercase().find_map(|c| self.map.get(&c)))
+                .or_else(|| c.to_uppercase().find_map(|c| self.map.get(&c)));
+
+            match replacement {
+                Some(replacement) => {
+                    for c in replacement.chars() {
+                        push(&mut result, allowed(c).unwrap_or(' '));
+                    }
+                }
+                None => {
+                    if let Some(placeholder) = self.placeholder {
+                        push(&mut result, allowed(placeholder).unwrap_or(' '));
+                    }
+                }
+            }
+        }
+
+        result.truncate(result.trim_end().len());
+        result
+    }
+}
+
+impl Default for TransliterationTable {
+    fn default() -> Self {
+        DIACRITICS
+            .iter()
+            .fold(TransliterationTable::empty(), |table, (from, to)| {
+                table.with(*from, to.to_string())
+            })
+    }
+}
+
+/// Combining diacritical marks Unicode block
+const COMBINING_MARKS: std::ops::RangeInclusive<char> = '\u{300}'..='\u{36F}';
+
+/// Character as allowed by the specification (uppercased), if it's allowed
+fn allowed(c: char) -> Option<char> {
+    match c {
+        '0'..='9' | 'A'..='Z' | ' ' | '$' | '%' | '+' | '-' | '.' | '/' | ':' => Some(c),
+        'a'..='z' => Some(c.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// Push a character, skipping leading and repeated spaces
+fn push(result: &mut String, c: char) {
+    if c != ' ' || !result.is_empty() && !result.ends_with(' ') {
+        result.push(c);
+    }
+}
+
+/// Compose decomposed (NFD) Czech and Slovak letters, so `c` + combining caron becomes `č`
+///
+/// Other combining marks are kept; this is not a full NFC normalization.
+pub(crate) fn compose(value: Cow<'_, str>) -> Cow<'_, str> {
+    if !value.contains(|c| COMBINING_MARKS.contains(&c)) {
+        return value;
+    }
+
+    let mut composed = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let base = c.to_ascii_lowercase();
+        let letter = chars.peek().and_then(|&mark| {
+            COMPOSITIONS
+                .iter()
+                .find(|(b, m, _)| *b == base && *m == mark)
+                .map(|(_, _, letter)| *letter)
+        });
+
+        match letter {
+            Some(letter) if c.is_ascii_uppercase() => {
+                composed.extend(letter.to_uppercase());
+                chars.next();
+            }
+            Some(letter) => {
+                composed.push(letter);
+                chars.next();
+            }
+            None => composed.push(c),
+        }
+    }
+
+    Cow::Owned(composed)
+}
+
+/// Strip surrounding ASCII whitespace and non-breaking spaces, as left by copying from a
+/// spreadsheet; spaces inside the value are kept
+pub(crate) fn trim(value: &str) -> &str {
+    value.trim_matches(|c: char| c.is_ascii_whitespace() || c == '\u{A0}')
+}
+
+/// Convert text to the character set allowed by the specification
+///
+/// Letters with diacritics are replaced with their ASCII base letters (`č` becomes `C`),
+/// letters are uppercased, unsupported characters (e.g. `&`) are replaced with a space and
+/// repeated spaces are collapsed. See [`TransliterationTable`] for other mappings.
+pub fn transliterate(value: &str) -> String {
+    TransliterationTable::default().transliterate(value)
+}
+
+/// Transliterate a recipient name and shorten it to the maximum of 35 characters
+pub fn recipient_translit(value: &str) -> String {
+    recipient_translit_with(value, &TransliterationTable::default())
+}
+
+/// Like [`recipient_translit`], with a custom transliteration table
+pub fn recipient_translit_with(value: &str, table: &TransliterationTable) -> String {
+    let mut result = table.transliterate(value);
+
+    result.truncate(limits::MAX_RECIPIENT_CHARS);
+    result.truncate(result.trim_end().len());
+    result
+}
+
+/// Change made to a value to make it valid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Adjustment {
+    /// Value was converted to the allowed character set
+    Transliterated {
+        /// Key of the field
+        key: SpaydKey,
+        /// Original value
+        from: String,
+        /// Converted value
+        to: String,
+    },
+
+    /// Value was shortened to the maximum length
+    Truncated {
+        /// Key of the field
+        key: SpaydKey,
+        /// Value before shortening
+        from: String,
+        /// Shortened value
+        to: String,
+        /// Maximum length in characters
+        max: usize,
+    },
+
+    /// Value was rewritten in its normal format (e.g. an IBAN without spaces)
+    Normalized {
+        /// Key of the field
+        key: SpaydKey,
+        /// Original value
+        from: String,
+        /// Normalized value
+        to: String,
+    },
+}
+
+impl Adjustment {
+    /// Key of the changed field
+    pub fn key(&self) -> &SpaydKey {
+        match self {
+            Adjustment::Transliterated { key, .. }
+            | Adjustment::Truncated { key, .. }
+            | Adjustment::Normalized { key, .. } => key,
+        }
+    }
+
+    /// Value before the change
+    pub fn from(&self) -> &str {
+        match self {
+            Adjustment::Transliterated { from, .. }
+            | Adjustment::Truncated { from, .. }
+            | Adjustment::Normalized { from, .. } => from,
+        }
+    }
+
+    /// Value after the change
+    pub fn to(&self) -> &str {
+        match self {
+            Adjustment::Transliterated { to, .. }
+            | Adjustment::Truncated { to, .. }
+            | Adjustment::Normalized { to, .. } => to,
+        }
+    }
+}
+
+/// Text made valid for a field, with the changes that were needed
+///
+/// Pass it to the builder's setter like a [`String`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LossyText {
+    value: String,
+    adjustments: Vec<Adjustment>,
+}
+
+impl LossyText {
+    /// Transliterate a value and shorten it to `max` characters as written with `escape`,
+    /// preferably at a word boundary
+    pub(crate) fn new(
+        key: SpaydKey,
+        value: &str,
+        max: usize,
+        table: &TransliterationTable,
+        escape: EscapePolicy,
+    ) -> Self {
+        let mut result = table.transliterate(value);
+        let mut adjustments = Vec::new();
+
+        if result != value {
+            adjustments.push(Adjustment::Transliterated {
+                key: key.clone(),
+                from: value.to_string(),
+                to: result.clone(),
+            });
+        }
+
+        // the transliterated text is ASCII, so bytes are characters; only `%` may be encoded
+        let width = |b: u8| match (b, escape) {
+            (b'%', EscapePolicy::PercentEncode) => 3,
+            _ => 1,
+        };
+
+        if result.bytes().map(width).sum::<usize>() > max {
+            let mut fit = 0;
+            let mut len = 0;
+
+            for b in result.bytes() {
+                len += width(b);
+
+                if len > max {
+                    break;
+                }
+
+                fit += 1;
+            }
+
+            let cut = match result[..fit].rfind(' ') {
+                Some(space) if result.as_bytes()[fit] != b' ' => space,
+                _ => fit,
+            };
+            let from = result.clone();
+
+            result.truncate(cut);
+            result.truncate(result.trim_end().len());
+            adjustments.push(Adjustment::Truncated {
+                key,
+                from,
+                to: result.clone(),
+                max,
+            });
+        }
+
+        LossyText {
+            value: result,
+            adjustments,
+        }
+    }
+
+    /// Resulting value
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// Changes made to the original value; empty if it was already valid
+    pub fn adjustments(&self) -> &[Adjustment] {
+        &self.adjustments
+    }
+}
+
+impl From<LossyText> for String {
+    fn from(text: LossyText) -> Self {
+        text.value
+    }
+}
+
+/// Make a message valid for the `MSG` field, transliterating it and shortening it to 60
+/// characters
+///
+/// The result passes validation unless it's empty.
+pub fn message_lossy(value: &str) -> LossyText {
+    message_lossy_with(value, &TransliterationTable::default())
+}
+
+/// Like [`message_lossy`], with a custom transliteration table
+pub fn message_lossy_with(value: &str, table: &TransliterationTable) -> LossyText {
+    LossyText::new(
+        SpaydKey::Msg,
+        value,
+        limits::MAX_MESSAGE_CHARS,
+        table,
+        EscapePolicy::Error,
+    )
+}
+
+impl Spayd {
+    /// Repair fields that don't pass validation where possible, returning the changes made
+    ///
+    /// `RN`, `MSG` and `PT` are transliterated and shortened, the IBAN, amount and phone
+    /// number are normalized (a comma is accepted as the decimal separator). Problems that can't be
+    /// repaired (e.g. a wrong IBAN checksum or a non-numeric amount) are returned as errors;
+    /// the repairable fields are changed even then.
+    pub fn sanitize(&mut self) -> Result<Vec<Adjustment>, SpaydError> {
+        self.sanitize_with(&TransliterationTable::default())
+    }
+
+    /// Like [`Spayd::sanitize`], with a custom transliteration table
+    pub fn sanitize_with(
+        &mut self,
+        table: &TransliterationTable,
+    ) -> Result<Vec<Adjustment>, SpaydError> {
+        let adjustments = self.repair(table);
+
+        self.validate()?;
+
+        Ok(adjustments)
+    }
+
+    /// Repaired copy of the payment, with the changes made and the problems that remain
+    ///
+    /// Repairs the same fields as [`Spayd::sanitize`]; fields that can't be repaired are left
+    /// as they are and all their errors are reported, in payload order.
+    pub fn sanitized(&self) -> (Spayd, SanitizeReport) {
+        let mut spayd = self.clone();
+        let changes = spayd.repair(&TransliterationTable::default());
+        let errors = spayd.validate_all().err().unwrap_or_default();
+
+        (spayd, SanitizeReport { changes, errors })
+    }
+
+    fn repair(&mut self, table: &TransliterationTable) -> Vec<Adjustment> {
+        let mut adjustments = Vec::new();
+
+        let account = iban::normalize(self.account.as_str().into()).into_owned();
+
+        if account != self.account {
+            adjustments.push(Adjustment::Normalized {
+                key: SpaydKey::Acc,
+                from: std::mem::replace(&mut self.account, account.clone()),
+                to: account,
+            });
+        }
+
+        let escape = self.validation.escape;
+        let mut repair = |key: SpaydKey, value: &mut String, max: usize| {
+            let text = LossyText::new(key, value, max, table, escape);
+
+            adjustments.extend(text.adjustments);
+            *value = text.value;
+        };
+
+        if let Some(recipient) = &mut self.recipient {
+            repair(SpaydKey::Rn, recipient, limits::MAX_RECIPIENT_CHARS);
+        }
+
+        if let Some(PaymentType::Other(payment_type)) = &mut self.payment_type {
+            repair(SpaydKey::Pt, payment_type, limits::MAX_PAYMENT_TYPE_CHARS);
+        }
+
+        if let Some(message) = &mut self.message {
+            repair(SpaydKey::Msg, message, limits::MAX_MESSAGE_CHARS);
+        }
+
+        if let Some(amount) = &mut self.amount {
+            match Amount::parse_lenient(amount).map(String::from) {
+                Ok(normalized) if normalized != *amount => {
+                    adjustments.push(Adjustment::Normalized {
+                        key: SpaydKey::Am,
+                        from: std::mem::replace(amount, normalized.clone()),
+                        to: normalized,
+                    });
+                }
+                // left as is and reported by validation
+                _ => {}
+            }
+        }
+
+        if let Some(Notification::Phone(phone)) = &mut self.notification {
+            let normalized = notify::normalize_phone(phone.as_str().into()).into_owned();
+
+            if normalized != *phone {
+                adjustments.push(Adjustment::Normalized {
+                    key: SpaydKey::Nta,
+                    from: std::mem::replace(phone, normalized.clone()),
+                    to: normalized,
+                });
+            }
+        }
+
+        adjustments
+    }
+
+    /// Copy with `RN`, `MSG` and `PT` uppercased, if [`SpaydBuilder::auto_uppercase`] is set
+    pub(super) fn uppercased(&self) -> Option<Spayd> {
+        if !self.auto_uppercase {
+            return None;
+        }
+
+        let mut spayd = self.clone();
+        spayd.auto_uppercase = false;
+
+        for value in [&mut spayd.recipient, &mut spayd.message]
+            .into_iter()
+            .flatten()
+        {
+            *value = value.to_uppercase();
+        }
+
+        if let Some(payment_type) = &mut spayd.payment_type {
+            *payment_type = PaymentType::from_code(&payment_type.as_str().to_uppercase());
+        }
+
+        Some(spayd)
+    }
+}
+
+/// Result of [`Spayd::sanitized`]
+#[derive(Debug, PartialEq)]
+pub struct SanitizeReport {
+    /// Changes made, in the order they were made
+    pub changes: Vec<Adjustment>,
+
+    /// Problems that couldn't be repaired, in payload order
+    pub errors: Vec<SpaydError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn transliterate_works() {
+        assert_eq!(transliterate("Nováček & synové"), "NOVACEK SYNOVE");
+        assert_eq!(transliterate("  Jan   Novák, s.r.o. "), "JAN NOVAK S.R.O.");
+        assert_eq!(
+            transliterate("Příliš žluťoučký kůň úpěl ďábelské ódy"),
+            "PRILIS ZLUTOUCKY KUN UPEL DABELSKE ODY"
+        );
+    }
+
+    #[test]
+    fn transliterate_czech_and_slovak_alphabets_works() {
+        let czech = "aábcčdďeéěfghiíjklmnňoópqrřsštťuúůvwxyýzž";
+        let slovak = "aáäbcčdďeéfghiíjklĺľmnňoóôpqrŕsštťuúvwxyýzž";
+
+        assert_eq!(
+            transliterate(czech),
+            "AABCCDDEEEFGHIIJKLMNNOOPQRRSSTTUUUVWXYYZZ"
+        );
+        assert_eq!(transliterate(&czech.to_uppercase()), transliterate(czech));
+        assert_eq!(
+            transliterate(slovak),
+            "AAABCCDDEEFGHIIJKLLLMNNOOOPQRRSSTTUUVWXYYZZ"
+        );
+        assert_eq!(transliterate(&slovak.to_uppercase()), transliterate(slovak));
+    }
+
+    #[test]
+    fn recipient_translit_works() {
+        for name in [
+            "Nováček & synové",
+            "Příliš žluťoučký kůň úpěl ďábelské ódy",
+            "Ľudovít Štúr a spoločníci, verejná obchodná spoločnosť",
+        ] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .recipient(recipient_translit(name))
+                .build();
+
+            assert!(spayd.spayd_string().is_ok(), "{}", name);
+        }
+
+        assert_eq!(
+            recipient_translit("Ľudovít Štúr a spoločníci, verejná obchodná spoločnosť"),
+            "LUDOVIT STUR A SPOLOCNICI VEREJNA O"
+        );
+    }
+
+    #[test]
+    fn transliteration_table_works() {
+        let table = TransliterationTable::default().with('ß', "ss");
+        let recipient = recipient_translit_with("Weiß GmbH", &table);
+
+        assert_eq!(recipient, "WEISS GMBH");
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient(recipient)
+            .build();
+
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*RN:WEISS GMBH"
+        );
+        assert_eq!(transliterate("Weiß"), "WEI");
+    }
+
+    #[test]
+    fn transliteration_placeholder_works() {
+        let table = TransliterationTable::default()
+            .with('ł', "L")
+            .with('ź', "Z");
+
+        assert_eq!(table.transliterate("Łódź 🎉 s.a."), "LODZ S.A.");
+        assert_eq!(
+            table.clone().placeholder(Some('-')).transliterate("Łódź🎉"),
+            "LODZ-"
+        );
+        assert_eq!(table.placeholder(None).transliterate("Ł&ódź 🎉"), "LODZ");
+        assert_eq!(
+            TransliterationTable::empty().transliterate("Nováček & synové"),
+            "NOV EK SYNOV"
+        );
+    }
+
+    #[test]
+    fn message_lossy_works() {
+        let message = message_lossy("Objednávka č. 1234*5 – děkujeme");
+
+        assert_eq!(message.as_str(), "OBJEDNAVKA C. 1234 5 DEKUJEME");
+        assert_eq!(
+            message.adjustments(),
+            [Adjustment::Transliterated {
+                key: SpaydKey::Msg,
+                from: "Objednávka č. 1234*5 – děkujeme".to_string(),
+                to: "OBJEDNAVKA C. 1234 5 DEKUJEME".to_string(),
+            }]
+        );
+
+        let message = message_lossy("PLATBA ZA ZBOZI");
+
+        assert_eq!(message.as_str(), "PLATBA ZA ZBOZI");
+        assert!(message.adjustments().is_empty());
+    }
+
+    #[test]
+    fn message_lossy_truncates() {
+        let text = "Platba za objednávku zboží z internetového obchodu, číslo 2024001";
+        let message = message_lossy(text);
+
+        assert_eq!(
+            message.as_str(),
+            "PLATBA ZA OBJEDNAVKU ZBOZI Z INTERNETOVEHO OBCHODU CISLO"
+        );
+        assert_eq!(
+            message.adjustments()[1],
+            Adjustment::Truncated {
+                key: SpaydKey::Msg,
+                from: "PLATBA ZA OBJEDNAVKU ZBOZI Z INTERNETOVEHO OBCHODU CISLO 2024001"
+                    .to_string(),
+                to: "PLATBA ZA OBJEDNAVKU ZBOZI Z INTERNETOVEHO OBCHODU CISLO".to_string(),
+                max: 60
+            }
+        );
+        assert_eq!(message.adjustments()[1].key(), &SpaydKey::Msg);
+        assert_eq!(
+            message.adjustments()[1].to(),
+            "PLATBA ZA OBJEDNAVKU ZBOZI Z INTERNETOVEHO OBCHODU CISLO"
+        );
+        assert_eq!(message.adjustments()[0].from(), text);
+
+        let message = message_lossy(&"A".repeat(65));
+
+        assert_eq!(message.as_str(), "A".repeat(60));
+
+        let message = message_lossy(&format!("{} {}", "A".repeat(60), "B"));
+
+        assert_eq!(message.as_str(), "A".repeat(60));
+
+        for text in [
+            text,
+            "1*2".repeat(30).as_str(),
+            "ěščřžýáíé ".repeat(10).as_str(),
+        ] {
+            let spayd = Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .message(message_lossy(text))
+                .build();
+
+            assert!(spayd.spayd_string().is_ok(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn sanitize_works() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("0239,50")
+            .recipient("Nováček & synové, výroba a prodej dřevěných hraček".to_string())
+            .message("Faktura č. 2024*001")
+            .payment_type(PaymentType::Other("ip".to_string()))
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+        assert_eq!(
+            spayd.sanitize().unwrap(),
+            [
+                Adjustment::Transliterated {
+                    key: SpaydKey::Rn,
+                    from: "Nováček & synové, výroba a prodej dřevěných hraček".to_string(),
+                    to: "NOVACEK SYNOVE VYROBA A PRODEJ DREVENYCH HRACEK".to_string(),
+                },
+                Adjustment::Truncated {
+                    key: SpaydKey::Rn,
+                    from: "NOVACEK SYNOVE VYROBA A PRODEJ DREVENYCH HRACEK".to_string(),
+                    to: "NOVACEK SYNOVE VYROBA A PRODEJ".to_string(),
+                    max: 35
+                },
+                Adjustment::Transliterated {
+                    key: SpaydKey::Pt,
+                    from: "ip".to_string(),
+                    to: "IP".to_string(),
+                },
+                Adjustment::Transliterated {
+                    key: SpaydKey::Msg,
+                    from: "Faktura č. 2024*001".to_string(),
+                    to: "FAKTURA C. 2024 001".to_string(),
+                },
+                Adjustment::Normalized {
+                    key: SpaydKey::Am,
+                    from: "0239,50".to_string(),
+                    to: "239.50".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*RN:NOVACEK SYNOVE VYROBA A PRODEJ*PT:IP*MSG:FAKTURA C. 2024 001"
+        );
+        assert_eq!(spayd.sanitize().unwrap(), []);
+    }
+
+    #[test]
+    fn sanitize_normalizes_phone() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .notify_phone("00420 777 123 456")
+            .build();
+
+        assert_eq!(
+            spayd.sanitize().unwrap(),
+            [Adjustment::Normalized {
+                key: SpaydKey::Nta,
+                from: "00420 777 123 456".to_string(),
+                to: "+420777123456".to_string(),
+            }]
+        );
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*NT:P*NTA:+420777123456"
+        );
+    }
+
+    #[test]
+    fn sanitize_unrepairable_fails() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567898".to_string())
+            .message("Děkujeme")
+            .build();
+
+        assert!(matches!(
+            spayd.sanitize(),
+            Err(SpaydError::InvalidAccountNumber(_))
+        ));
+
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("abc")
+            .build();
+
+        assert_eq!(spayd.sanitize(), Err(SpaydError::AmountNotDecimal));
+    }
+
+    #[test]
+    fn sanitized_works() {
+        let spayd = Spayd::builder()
+            .account("cz55 0800 0000 0012 3456 7899".to_string())
+            .amount("abc")
+            .message("Děkujeme")
+            .notify_phone("+420 777 123 456")
+            .build();
+
+        let (sanitized, report) = spayd.sanitized();
+
+        assert_eq!(
+            report,
+            SanitizeReport {
+                changes: vec![
+                    Adjustment::Transliterated {
+                        key: SpaydKey::Msg,
+                        from: "Děkujeme".to_string(),
+                        to: "DEKUJEME".to_string(),
+                    },
+                    Adjustment::Normalized {
+                        key: SpaydKey::Nta,
+                        from: "+420 777 123 456".to_string(),
+                        to: "+420777123456".to_string(),
+                    },
+                ],
+                errors: vec![SpaydError::AmountNotDecimal],
+            }
+        );
+        assert_eq!(sanitized.get(SpaydKey::Am).as_deref(), Some("abc"));
+        assert_eq!(sanitized.get(SpaydKey::Msg).as_deref(), Some("DEKUJEME"));
+        assert_eq!(spayd.get(SpaydKey::Msg).as_deref(), Some("Děkujeme"));
+
+        let (sanitized, report) = sanitized.sanitized();
+
+        assert_eq!(report.changes, []);
+        assert!(!sanitized.is_valid());
+    }
+
+    #[test]
+    fn lossy_works() {
+        let spayd = |lossy: bool, level: ValidationLevel| {
+            Spayd::builder()
+                .account("cz55 0800 0000 0012 3456 7899".to_string())
+                .amount("0239,50")
+                .message("Děkujeme")
+                .validation(ValidationOptions {
+                    level,
+                    ..Default::default()
+                })
+                .lossy(lossy)
+                .build()
+        };
+
+        assert!(spayd(false, ValidationLevel::Standard)
+            .spayd_string()
+            .is_err());
+
+        let lossy = spayd(true, ValidationLevel::Strict);
+        let expected = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:DEKUJEME";
+
+        assert_eq!(lossy.spayd_string().unwrap(), expected);
+        assert_eq!(lossy.spayd_string_report().unwrap(), expected);
+        assert_eq!(lossy.sanitized().1.changes.len(), 2);
+
+        // the level's checks still apply after the repairs
+        let mut lossy = lossy;
+        lossy.currency = Some("XAU".to_string());
+
+        assert_eq!(
+            lossy.spayd_string(),
+            Err(SpaydError::CurrencyNotPermitted {
+                currency: CurrencyCode::try_from("XAU").unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn auto_uppercase_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("Novak s.r.o.".to_string())
+            .payment_type(PaymentType::Other("ip".to_string()))
+            .message("Platba za zbozi")
+            .auto_uppercase(true)
+            .build();
+        let expected =
+            "SPD*1.0*ACC:CZ5508000000001234567899*RN:NOVAK S.R.O.*PT:IP*MSG:PLATBA ZA ZBOZI";
+
+        assert_eq!(spayd.spayd_string().unwrap(), expected);
+        assert_eq!(
+            spayd.canonical_string().unwrap(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*MSG:PLATBA ZA ZBOZI*PT:IP*RN:NOVAK S.R.O."
+        );
+        assert_eq!(spayd.validate_all(), Ok(()));
+        assert_eq!(
+            spayd.uppercased().unwrap().payment_type,
+            Some(PaymentType::Instant)
+        );
+        assert_eq!(spayd.get(SpaydKey::Rn).as_deref(), Some("Novak s.r.o."));
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("Novák s.r.o.".to_string())
+            .message("Platba za zboží")
+            .auto_uppercase(true)
+            .build();
+
+        assert_eq!(
+            spayd.uppercased().unwrap().get(SpaydKey::Msg).as_deref(),
+            Some("PLATBA ZA ZBOŽÍ")
+        );
+        assert_eq!(
+            spayd.spayd_string(),
+            Err(SpaydError::InvalidRecipient(
+                "Value contains forbidden character(s)"
+            ))
+        );
+
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .recipient("Novak s.r.o.".to_string())
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+    }
+
+    #[test]
+    fn transliterate_decomposed_works() {
+        let nfc = "Nov\u{e1}\u{10d}ek \u{17d}lu\u{165}ou\u{10d}k\u{fd}";
+        let nfd = "Nova\u{301}c\u{30c}ek Z\u{30c}lut\u{30c}ouc\u{30c}ky\u{301}";
+        let spayd = |recipient: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .recipient(recipient_translit(recipient))
+                .build()
+                .spayd_string()
+        };
+
+        assert_eq!(transliterate(nfd), "NOVACEK ZLUTOUCKY");
+        assert_eq!(
+            TransliterationTable::default()
+                .with('ř', "RZ")
+                .transliterate("R\u{30c}i\u{301}ha"),
+            "RZIHA"
+        );
+        assert_eq!(spayd(nfd).unwrap(), spayd(nfc).unwrap());
+    }
+
+    #[test]
+    fn compose_works() {
+        assert_eq!(
+            text::compose("Nova\u{301}c\u{30c}ek Z\u{30c}LUT\u{30c}OUC\u{30c}KY\u{301}".into()),
+            "Nováček ŽLUŤOUČKÝ"
+        );
+        assert_eq!(text::compose("u\u{30a}l o\u{302}a\u{308}".into()), "ůl ôä");
+        assert_eq!(text::compose("q\u{301} \u{301}".into()), "q\u{301} \u{301}");
+        assert!(matches!(text::compose("Nováček".into()), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn decomposed_text_is_composed() {
+        let nfc = "NOV\u{c1}\u{10c}EK";
+        let nfd = "NOVA\u{301}C\u{30c}EK";
+        let spayd = |text: &str| {
+            Spayd::builder()
+                .account("CZ5508000000001234567899".to_string())
+                .recipient(text.to_string())
+                .message(text)
+                .validation(ValidationOptions {
+                    escape: EscapePolicy::PercentEncode,
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        assert_eq!(spayd(nfd).recipient.as_deref(), Some(nfc));
+        assert_eq!(
+            spayd(nfd).spayd_string().unwrap(),
+            spayd(nfc).spayd_string().unwrap()
+        );
+
+        let mut spayd = spayd(nfc);
+        spayd.set(SpaydKey::Msg, nfd).unwrap();
+        assert_eq!(spayd.message.as_deref(), Some(nfc));
+
+        let parsed = Spayd::parse_with(
+            "SPD*1.0*ACC:CZ5508000000001234567899*RN:NOVA%CC%81K*MSG:C%CC%8CAJ",
+            &ParseOptions {
+                validation: spayd.validation.clone(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(parsed.recipient.as_deref(), Some("NOVÁK"));
+        assert_eq!(parsed.message.as_deref(), Some("ČAJ"));
+    }
+
+    #[test]
+    fn sanitize_counts_encoded_length() {
+        let mut spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .message(format!("{}%", "A".repeat(58)))
+            .validation(ValidationOptions {
+                escape: EscapePolicy::PercentEncode,
+                ..Default::default()
+            })
+            .build();
+
+        assert!(spayd.spayd_string().is_err());
+        assert_eq!(
+            spayd.sanitize().unwrap(),
+            [Adjustment::Truncated {
+                key: SpaydKey::Msg,
+                from: format!("{}%", "A".repeat(58)),
+                to: "A".repeat(58),
+                max: 60
+            }]
+        );
+        assert_eq!(
+            spayd.spayd_string().unwrap(),
+            format!(
+                "SPD*1.0*ACC:CZ5508000000001234567899*MSG:{}",
+                "A".repeat(58)
+            )
+        );
+    }
+}
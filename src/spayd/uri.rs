@@ -0,0 +1,196 @@
+use super::encoding::{decode, encode_uri_component};
+use super::*;
+
+impl Spayd {
+    /// Generate a `spayd://` payment URI
+    pub fn to_uri(&self) -> Result<String, SpaydError> {
+        self.to_uri_with_scheme("spayd://")
+    }
+
+    /// Generate a payment URI with custom prefix (e.g. `https://pay.example.com/?spayd=`)
+    ///
+    /// The validated SPAYD string is appended to the prefix with RFC 3986 reserved
+    /// characters percent-encoded.
+    pub fn to_uri_with_scheme(&self, scheme: &str) -> Result<String, SpaydError> {
+        let payload = self.spayd_string()?;
+
+        Ok(format!("{}{}", scheme, encode_uri_component(&payload)))
+    }
+
+    /// Parse a payment deep link
+    ///
+    /// Accepts `spayd:` and `SPD:` URIs (with or without `//`), `http(s)` links carrying
+    /// the payload in a query parameter, and bare SPAYD strings. The payload is
+    /// percent-decoded before being parsed.
+    pub fn from_uri(uri: &str) -> Result<Spayd, SpaydParseError> {
+        let uri = uri.trim();
+
+        let (offset, payload) = match uri.split_once(':') {
+            Some((scheme, rest))
+                if scheme.eq_ignore_ascii_case("spayd") || scheme.eq_ignore_ascii_case("spd") =>
+            {
+                let rest = rest.strip_prefix("//").unwrap_or(rest);
+                (uri.len() - rest.len(), rest)
+            }
+            Some((scheme, rest))
+                if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") =>
+            {
+                let payload = query_payload(rest).ok_or(SpaydParseError::MissingHeader)?;
+                (payload.as_ptr() as usize - uri.as_ptr() as usize, payload)
+            }
+            _ => (0, uri),
+        };
+
+        let payload = decode(payload).map_err(|e| SpaydParseError::InvalidEscape {
+            sequence: e.sequence,
+            offset: offset + e.offset,
+        })?;
+
+        if !has_header(&payload) {
+            return Err(SpaydParseError::MissingHeader);
+        }
+
+        Spayd::parse(&payload)
+    }
+}
+
+/// Find the query parameter carrying a SPAYD payload
+fn query_payload(rest: &str) -> Option<&str> {
+    let (_, query) = rest.split_once('?')?;
+    let query = query.split('#').next().unwrap_or(query);
+
+    query
+        .split('&')
+        .map(|param| param.split_once('=').map_or(param, |(_, value)| value))
+        .find(|value| decode(value).is_ok_and(|value| has_header(&value)))
+}
+
+fn has_header(payload: &str) -> bool {
+    payload
+        .get(..4)
+        .is_some_and(|header| header.eq_ignore_ascii_case("SPD*"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    const PAYLOAD: &str = "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA ZA ZBOZI";
+
+    #[test]
+    fn from_uri_spayd_scheme_works() {
+        let spayd = Spayd::from_uri(
+            "spayd://SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA%20ZA%20ZBOZI",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), PAYLOAD);
+
+        let spayd = Spayd::from_uri(
+            "spayd:SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA ZA ZBOZI",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn from_uri_spd_scheme_works() {
+        let spayd = Spayd::from_uri(
+            "SPD:SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50*MSG:PLATBA%20ZA%20ZBOZI",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn from_uri_https_query_works() {
+        let spayd = Spayd::from_uri(
+            "https://pay.example.com/qr?lang=cs&spayd=SPD%2A1.0%2AACC%3ACZ5508000000001234567899\
+             %2AAM%3A239.50%2AMSG%3APLATBA%20ZA%20ZBOZI#top",
+        )
+        .unwrap();
+
+        assert_eq!(spayd.spayd_string().unwrap(), PAYLOAD);
+    }
+
+    #[test]
+    fn to_uri_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .message("10 % OFF".to_string())
+            .build();
+
+        let uri = spayd.to_uri().unwrap();
+
+        assert_eq!(
+            uri,
+            "spayd://SPD%2A1.0%2AACC%3ACZ5508000000001234567899%2AAM%3A239.50\
+             %2AMSG%3A10%20%25%20OFF"
+        );
+        assert_eq!(
+            Spayd::from_uri(&uri).unwrap().spayd_string(),
+            spayd.spayd_string()
+        );
+    }
+
+    #[test]
+    fn to_uri_with_scheme_works() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50".to_string())
+            .build();
+
+        let uri = spayd
+            .to_uri_with_scheme("https://pay.example.com/?spayd=")
+            .unwrap();
+
+        assert_eq!(
+            uri,
+            "https://pay.example.com/?spayd=SPD%2A1.0%2AACC%3ACZ5508000000001234567899%2AAM%3A239.50"
+        );
+        assert_eq!(
+            Spayd::from_uri(&uri).unwrap().spayd_string(),
+            spayd.spayd_string()
+        );
+    }
+
+    #[test]
+    fn to_uri_validates() {
+        let spayd = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.500".to_string())
+            .build();
+
+        assert!(matches!(
+            spayd.to_uri(),
+            Err(SpaydError::AmountTooManyDecimals { found: 3 })
+        ));
+    }
+
+    #[test]
+    fn from_uri_without_header_fails() {
+        let result = Spayd::from_uri("spayd://ACC:CZ5508000000001234567899*AM:239.50");
+
+        assert_eq!(result.unwrap_err(), SpaydParseError::MissingHeader);
+
+        let result = Spayd::from_uri("https://pay.example.com/qr?amount=239.50");
+
+        assert_eq!(result.unwrap_err(), SpaydParseError::MissingHeader);
+    }
+
+    #[test]
+    fn from_uri_invalid_escape_fails() {
+        let result = Spayd::from_uri("spayd://SPD*1.0*ACC:CZ5508000000001234567899*AM:%2");
+
+        assert_eq!(
+            result.unwrap_err(),
+            SpaydParseError::InvalidEscape {
+                sequence: "%2".to_string(),
+                offset: 48
+            }
+        );
+    }
+}
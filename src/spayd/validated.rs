@@ -0,0 +1,105 @@
+use std::ops::Deref;
+
+use super::*;
+
+/// Payment that passed [`Spayd::validate`], created by [`Spayd::into_validated`]
+///
+/// It can't be changed; to edit it, take it apart with [`ValidatedSpayd::into_inner`] and
+/// validate it again.
+#[derive(Debug, Clone)]
+pub struct ValidatedSpayd(Spayd);
+
+impl ValidatedSpayd {
+    /// Generate SPAYD string
+    pub fn spayd_string(&self) -> String {
+        self.0.build_string()
+    }
+
+    /// Generate payment QR code
+    ///
+    /// Fails only if the string doesn't fit into a QR code.
+    #[cfg(feature = "qrcode")]
+    pub fn qrcode(&self) -> qrcode::types::QrResult<qrcode::QrCode> {
+        qrcode::QrCode::new(self.spayd_string())
+    }
+
+    /// Validated payment
+    pub fn as_inner(&self) -> &Spayd {
+        &self.0
+    }
+
+    /// Payment, to be changed and validated again
+    pub fn into_inner(self) -> Spayd {
+        self.0
+    }
+}
+
+impl Deref for ValidatedSpayd {
+    type Target = Spayd;
+
+    fn deref(&self) -> &Spayd {
+        &self.0
+    }
+}
+
+impl Spayd {
+    /// Validate the payment, keeping the result in its type
+    ///
+    /// On failure, the payment is returned together with the first error.
+    #[allow(clippy::result_large_err)] // the payment is handed back to the caller
+    pub fn into_validated(self) -> Result<ValidatedSpayd, (Spayd, SpaydError)> {
+        match self.validate() {
+            Ok(()) => Ok(ValidatedSpayd(self)),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::*;
+
+    #[test]
+    fn into_validated_works() {
+        let validated = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .amount("239.50")
+            .build()
+            .into_validated()
+            .unwrap();
+
+        assert_eq!(
+            validated.spayd_string(),
+            "SPD*1.0*ACC:CZ5508000000001234567899*AM:239.50"
+        );
+        assert_eq!(validated.amount_minor(), Some(23950));
+        assert_eq!(
+            validated.as_inner().get(SpaydKey::Am).as_deref(),
+            Some("239.50")
+        );
+
+        let mut spayd = validated.into_inner();
+        spayd.set(SpaydKey::Msg, "PLATBA").unwrap();
+        spayd.message = Some("A*B".to_string());
+
+        let (spayd, error) = spayd.into_validated().unwrap_err();
+
+        assert_eq!(
+            error,
+            SpaydError::InvalidMessage("Value contains forbidden character(s)")
+        );
+        assert_eq!(spayd.get(SpaydKey::Msg).as_deref(), Some("A*B"));
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn validated_qrcode_works() {
+        let validated = Spayd::builder()
+            .account("CZ5508000000001234567899".to_string())
+            .build()
+            .into_validated()
+            .unwrap();
+
+        assert!(validated.qrcode().is_ok());
+    }
+}
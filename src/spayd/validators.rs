@@ -0,0 +1,181 @@
+//! Checks of single field values, as done by [`Spayd::validate`] with the default
+//! [`ValidationOptions`]
+//!
+//! Useful for validating input before a whole payment can be built.
+
+use super::*;
+
+fn empty() -> Fields<'static> {
+    static OPTIONS: OnceLock<ValidationOptions> = OnceLock::new();
+
+    Fields::empty(OPTIONS.get_or_init(ValidationOptions::default))
+}
+
+/// IBAN (`ACC`): format, length for the country and check digits
+///
+/// Spaces and lowercase letters are accepted, as by the builder.
+pub fn validate_iban(iban: &str) -> Result<(), SpaydError> {
+    let iban = iban::normalize(iban.into());
+
+    Fields {
+        account: &iban,
+        ..empty()
+    }
+    .validate_account()
+}
+
+/// Amount (`AM`): decimal number with at most 2 decimal places, at most 10 characters and at
+/// most 9 999 999.99
+pub fn validate_amount(amount: &str) -> Result<(), SpaydError> {
+    Fields {
+        amount: Some(amount),
+        ..empty()
+    }
+    .validate_amount()
+}
+
+/// Currency (`CC`): ISO 4217 alphabetic code
+pub fn validate_currency(currency: &str) -> Result<(), SpaydError> {
+    Fields {
+        currency: Some(currency),
+        ..empty()
+    }
+    .validate_currency()
+}
+
+/// Reference (`RF`): at most 16 digits
+pub fn validate_reference(reference: &str) -> Result<(), SpaydError> {
+    Fields {
+        reference: Some(reference),
+        ..empty()
+    }
+    .validate_reference()
+}
+
+/// Recipient name (`RN`): at most 35 allowed characters
+pub fn validate_recipient(recipient: &str) -> Result<(), SpaydError> {
+    Fields {
+        recipient: Some(recipient),
+        ..empty()
+    }
+    .validate_recipient()
+}
+
+/// Due date (`DT`): existing date in the `YYYYMMDD` format, years 1000-2999
+pub fn validate_date(date: &str) -> Result<(), SpaydError> {
+    Fields {
+        date: Some(date),
+        ..empty()
+    }
+    .validate_date()
+}
+
+/// Payment type (`PT`): at most 3 uppercase letters or digits
+pub fn validate_payment_type(payment_type: &str) -> Result<(), SpaydError> {
+    Fields {
+        payment_type: Some(payment_type),
+        ..empty()
+    }
+    .validate_payment_type()
+}
+
+/// Message (`MSG`): at most 60 allowed characters
+pub fn validate_message(message: &str) -> Result<(), SpaydError> {
+    Fields {
+        message: Some(message),
+        ..empty()
+    }
+    .validate_message()
+}
+
+/// Email notification address (`NTA` with `NT:E`): at most 320 characters, of which at most 64
+/// before the `@` and at most 255 after it
+pub fn validate_email(address: &str) -> Result<(), SpaydError> {
+    Fields {
+        notify: Some(NotifyType::Email),
+        notify_address: Some(address),
+        ..empty()
+    }
+    .validate_notify_address()
+}
+
+/// Phone notification address (`NTA` with `NT:P`): 7 to 15 digits, optionally prefixed with `+`,
+/// at most 20 characters
+pub fn validate_phone(number: &str) -> Result<(), SpaydError> {
+    Fields {
+        notify: Some(NotifyType::Phone),
+        notify_address: Some(number),
+        ..empty()
+    }
+    .validate_notify_address()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spayd::validators::*;
+
+    #[test]
+    fn validate_iban_works() {
+        assert_eq!(validate_iban("CZ5508000000001234567899"), Ok(()));
+        assert_eq!(validate_iban("cz55 0800 0000 0012 3456 7899"), Ok(()));
+        assert_eq!(
+            validate_iban("CZ5508000000001234567898"),
+            Err(SpaydError::InvalidAccountNumber(
+                "IBAN check digits do not match"
+            ))
+        );
+        assert!(validate_iban("").is_err());
+    }
+
+    #[test]
+    fn validate_amount_works() {
+        assert_eq!(validate_amount("239.50"), Ok(()));
+        assert_eq!(
+            validate_amount("1.234"),
+            Err(SpaydError::AmountTooManyDecimals { found: 3 })
+        );
+        assert_eq!(
+            validate_amount("12345678.90"),
+            Err(SpaydError::AmountTooLong { len: 11, max: 10 })
+        );
+        assert_eq!(validate_amount("1,5"), Err(SpaydError::AmountNotDecimal));
+    }
+
+    #[test]
+    fn validate_text_works() {
+        assert_eq!(validate_currency("EUR"), Ok(()));
+        assert!(validate_currency("XYZ").is_err());
+        assert_eq!(validate_reference("1234567890123456"), Ok(()));
+        assert!(validate_reference("12345678901234567").is_err());
+        assert!(validate_reference("12A").is_err());
+        assert_eq!(validate_recipient("JAN NOVAK"), Ok(()));
+        assert!(validate_recipient(&"A".repeat(36)).is_err());
+        assert_eq!(validate_message("PLATBA"), Ok(()));
+        assert!(validate_message("A*B").is_err());
+        assert_eq!(validate_payment_type("IP"), Ok(()));
+        assert!(validate_payment_type("ip").is_err());
+    }
+
+    #[test]
+    fn validate_date_works() {
+        assert_eq!(validate_date("20240229"), Ok(()));
+        assert!(validate_date("20230229").is_err());
+        assert!(validate_date("2024-02-29").is_err());
+    }
+
+    #[test]
+    fn validate_notify_address_works() {
+        assert_eq!(validate_email("jan@example.com"), Ok(()));
+        assert_eq!(
+            validate_email("jan@example"),
+            Err(SpaydError::InvalidNotifyAddress("Invalid email address"))
+        );
+        assert_eq!(validate_phone("+420777123456"), Ok(()));
+        assert_eq!(
+            validate_phone("12345"),
+            Err(SpaydError::InvalidNotifyAddress(
+                "Phone number is too short"
+            ))
+        );
+    }
+}
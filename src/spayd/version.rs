@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// SPAYD format version
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpaydVersion {
+    /// Version `1.0`
+    #[default]
+    V1_0,
+
+    /// Version `1.1` (accepted when parsing only with [`ParseOptions::allow_version_1_1`])
+    ///
+    /// [`ParseOptions::allow_version_1_1`]: super::ParseOptions::allow_version_1_1
+    V1_1,
+}
+
+impl SpaydVersion {
+    /// Version as it appears in the SPAYD string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpaydVersion::V1_0 => "1.0",
+            SpaydVersion::V1_1 => "1.1",
+        }
+    }
+}
+
+impl fmt::Display for SpaydVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}